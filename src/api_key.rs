@@ -1 +1,159 @@
-pub const API_KEY: &str = "";
+//! API keys for the services that need one, read at runtime so they never have to be compiled in.
+
+use std::{ collections::BTreeMap, env, fs, path::{ Path, PathBuf } };
+
+use crate::config::Config;
+use crate::models::errors::ApiError;
+
+/// The key that SerpApi spelling suggestions are requested with.
+pub const SERPAPI: &str = "serpapi";
+
+/// Environment variable that overrides the location of the secrets file.
+const SECRETS_ENV: &str = "THESAURUST_SECRETS";
+
+/// Returns the environment variable that holds the key for `service`, such as `THESAURUST_SERPAPI_KEY`.
+pub fn env_var(service: &str) -> String {
+    format!("THESAURUST_{}_KEY", service.to_uppercase().replace('-', "_"))
+}
+
+/// Returns the key for `service`: its environment variable first, then the secrets file.
+/// A secrets file that other users can read is refused rather than trusted.
+pub fn get(service: &str) -> Result<Option<String>, ApiError> {
+    if let Some(key) = env::var(env_var(service)).ok().filter(|key| !key.is_empty()) {
+        return Ok(Some(key));
+    }
+    match path() {
+        Some(path) if path.exists() => read_from(&path, service),
+        _ => Ok(None),
+    }
+}
+
+/// Saves the key for `service` to the secrets file, or removes it if `key` is empty.
+/// Returns where it was saved.
+pub fn set(service: &str, key: &str) -> Result<PathBuf, ApiError> {
+    let path = path().ok_or_else(|| ApiError::KeyError(String::from("Cannot find a config directory")))?;
+    write_to(&path, service, key)?;
+    Ok(path)
+}
+
+/// Returns the location of the secrets file, `secrets.toml` next to the config file.
+pub fn path() -> Option<PathBuf> {
+    match env::var_os(SECRETS_ENV) {
+        Some(path) => Some(PathBuf::from(path)),
+        None => Config::path().and_then(|config| Some(config.parent()?.join("secrets.toml"))),
+    }
+}
+
+fn read_from(path: &Path, service: &str) -> Result<Option<String>, ApiError> {
+    check_permissions(path)?;
+    Ok(parse(path)?.remove(service).filter(|key| !key.is_empty()))
+}
+
+fn write_to(path: &Path, service: &str, key: &str) -> Result<(), ApiError> {
+    let mut keys = if path.exists() { parse(path)? } else { BTreeMap::new() };
+    if key.is_empty() {
+        keys.remove(service);
+    } else {
+        keys.insert(service.to_string(), key.to_string());
+    }
+    let contents = toml::to_string(&keys).map_err(|err| ApiError::KeyError(err.to_string()))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    write_private(path, &contents)
+}
+
+fn parse(path: &Path) -> Result<BTreeMap<String, String>, ApiError> {
+    let contents = fs::read_to_string(path)?;
+    toml::from_str(&contents).map_err(|err| {
+        ApiError::KeyError(format!("Invalid secrets file {}: {}", path.display(), err.message()))
+    })
+}
+
+#[cfg(unix)]
+fn check_permissions(path: &Path) -> Result<(), ApiError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = fs::metadata(path)?.permissions().mode();
+    if mode & 0o077 != 0 {
+        return Err(
+            ApiError::KeyError(
+                format!(
+                    "{} can be read by other users; run `chmod 600 {}` to use it",
+                    path.display(),
+                    path.display()
+                )
+            )
+        );
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_permissions(_path: &Path) -> Result<(), ApiError> {
+    Ok(())
+}
+
+/// Writes `contents` to `path` so that only the owner can read it, tightening an existing file.
+#[cfg(unix)]
+fn write_private(path: &Path, contents: &str) -> Result<(), ApiError> {
+    use std::{ io::Write, os::unix::fs::{ OpenOptionsExt, PermissionsExt } };
+
+    let mut file = fs::OpenOptions::new().write(true).create(true).truncate(true).mode(0o600).open(path)?;
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    file.write_all(contents.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, contents: &str) -> Result<(), ApiError> {
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn temp_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("thesaurust-{}-{}", std::process::id(), name)).join("secrets.toml")
+    }
+
+    #[test]
+    fn test_env_var() {
+        assert_eq!("THESAURUST_SERPAPI_KEY", env_var(SERPAPI));
+        assert_eq!("THESAURUST_SOME_SERVICE_KEY", env_var("some-service"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_saves_keys_privately() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = temp_path("save");
+        write_to(&path, SERPAPI, "secret").unwrap();
+        write_to(&path, "other", "another").unwrap();
+        assert_eq!(0o600, fs::metadata(&path).unwrap().permissions().mode() & 0o777);
+        assert_eq!(Some(String::from("secret")), read_from(&path, SERPAPI).unwrap());
+        write_to(&path, SERPAPI, "").unwrap();
+        assert_eq!(None, read_from(&path, SERPAPI).unwrap());
+        assert_eq!(Some(String::from("another")), read_from(&path, "other").unwrap());
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_refuses_readable_key_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = temp_path("readable");
+        write_to(&path, SERPAPI, "secret").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(matches!(read_from(&path, SERPAPI), Err(ApiError::KeyError(_))));
+        // Saving a key again makes the file private.
+        write_to(&path, SERPAPI, "secret").unwrap();
+        assert_eq!(Some(String::from("secret")), read_from(&path, SERPAPI).unwrap());
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
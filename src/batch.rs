@@ -0,0 +1,136 @@
+//! Looks up many words at once for `thesaurust batch`, writing a line of JSON for each.
+
+use std::{ collections::HashMap, io::{ self, BufRead, Write }, sync::Arc };
+
+use tokio::{ sync::Semaphore, task::JoinSet };
+
+use crate::cache::Cache;
+use crate::client::{ parse_response, WordInfo };
+use crate::models::errors::ErrorReport;
+use crate::print::{ Filter, Output };
+use crate::providers::{ LookupOptions, Provider };
+
+/// Reads the words to look up from `input`, one per line, skipping blank lines.
+pub fn read_words(input: impl BufRead) -> io::Result<Vec<String>> {
+    let mut words = Vec::new();
+    for line in input.lines() {
+        let line = line?;
+        let word = line.trim();
+        if !word.is_empty() {
+            words.push(word.to_string());
+        }
+    }
+    Ok(words)
+}
+
+/// Looks `words` up, at most `parallel` at a time, and writes the results to `out` as they
+/// finish, in the same schema as `--json` with one word per line. Words that cannot be found
+/// are written with their error, as are words whose lookup failed unexpectedly. Stops early
+/// if `out` is closed.
+pub async fn look_up(
+    provider: Provider,
+    cache: Option<Cache>,
+    options: LookupOptions,
+    words: Vec<String>,
+    filter: Filter,
+    parallel: usize,
+    out: &mut impl Write
+) -> io::Result<()> {
+    let semaphore = Arc::new(Semaphore::new(parallel.max(1)));
+    // Dropping the set when writing fails aborts the lookups that are left.
+    let mut lookups = JoinSet::new();
+    // The word of each lookup, for reporting the lookups that panic.
+    let mut looking_up = HashMap::new();
+    for word in words {
+        let (provider, cache, options) = (provider.clone(), cache.clone(), options.clone());
+        let (semaphore, filter) = (semaphore.clone(), filter.clone());
+        let task = lookups.spawn({
+            let word = word.clone();
+            async move {
+                let _permit = semaphore.acquire_owned().await;
+                let results = parse_response(&*provider, cache.as_ref(), &options, word.clone(), false).await;
+                filter.apply(&word, results)
+            }
+        });
+        looking_up.insert(task.id(), word);
+    }
+    while let Some(joined) = lookups.join_next_with_id().await {
+        let (word, results) = match joined {
+            Ok((id, results)) => (looking_up.remove(&id).unwrap_or_default(), results),
+            Err(err) => {
+                let word = looking_up.remove(&err.id()).unwrap_or_default();
+                let report = ErrorReport {
+                    title: String::from("Lookup failed"),
+                    message: format!("The lookup of \"{}\" stopped unexpectedly", word),
+                    hint: None,
+                };
+                (word, WordInfo::failed(report))
+            }
+        };
+        let line = serde_json::to_string(&Output::new(&word, &results))?;
+        match writeln!(out, "{}", line).and_then(|_| out.flush()) {
+            Err(err) if err.kind() == io::ErrorKind::BrokenPipe => {
+                return Ok(());
+            }
+            result => result?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::models::{ data::{ Meaning, Thesaurus }, errors::ApiError };
+    use crate::providers::DictionaryProvider;
+    use pretty_assertions::assert_eq;
+
+    /// A provider that knows every word but `zzz`.
+    struct Known;
+
+    #[async_trait]
+    impl DictionaryProvider for Known {
+        fn name(&self) -> &str {
+            "known"
+        }
+
+        async fn lookup(&self, word: &str, _options: &LookupOptions) -> Result<Vec<Thesaurus>, ApiError> {
+            if word == "zzz" {
+                return Err(ApiError::NotFound);
+            }
+            if word == "boom" {
+                panic!("cannot look up {}", word);
+            }
+            let meanings = Some(vec![Meaning::default()]);
+            Ok(vec![Thesaurus { word: Some(word.to_string()), meanings, ..Thesaurus::default() }])
+        }
+    }
+
+    #[test]
+    fn test_read_words() {
+        let input = "hello\n\n  world \n";
+        assert_eq!(vec![String::from("hello"), String::from("world")], read_words(input.as_bytes()).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_look_up() {
+        let words = ["hello", "zzz", "world", "boom"].map(String::from).to_vec();
+        let mut out = Vec::new();
+        let options = LookupOptions::default();
+        look_up(Provider::new(Known), None, options, words, Filter::default(), 2, &mut out).await.unwrap();
+        let mut lines: Vec<serde_json::Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        lines.sort_by_key(|line| line["word"].as_str().unwrap().to_string());
+        assert_eq!(4, lines.len());
+        assert_eq!("Lookup failed", lines[0]["error"]["title"]);
+        assert_eq!("boom", lines[0]["word"]);
+        assert_eq!("hello", lines[1]["entries"][0]["word"]);
+        assert_eq!("world", lines[2]["entries"][0]["word"]);
+        assert_eq!("Not found", lines[3]["error"]["title"]);
+    }
+}
@@ -0,0 +1,176 @@
+//! Keeps the results of lookups on disk, so that words looked up before are shown at once and offline.
+
+use std::{
+    fs,
+    io,
+    path::{ Path, PathBuf },
+    time::{ Duration, SystemTime, UNIX_EPOCH },
+};
+
+use serde_derive::{ Deserialize, Serialize };
+
+use crate::models::data::Thesaurus;
+use crate::providers::LookupOptions;
+
+/// Where the results of a lookup came from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Origin {
+    Cache,
+    Network,
+    /// Looked up from dictionary files or a server on this machine, which are not cached.
+    Local,
+}
+
+/// What is stored for a lookup.
+#[derive(Deserialize, Serialize)]
+struct Record {
+    key: String,
+    /// When the results were fetched, in seconds since the Unix epoch.
+    fetched: u64,
+    entries: Vec<Thesaurus>,
+}
+
+/// Results found in the cache.
+#[derive(Clone, Debug)]
+pub struct Hit {
+    pub entries: Vec<Thesaurus>,
+    /// Whether the results are older than the time to live. Expired results are only
+    /// shown when the provider cannot be reached.
+    pub expired: bool,
+}
+
+/// A directory of lookup results with one file per provider, language and word.
+#[derive(Clone, Debug)]
+pub struct Cache {
+    dir: PathBuf,
+    ttl: Duration,
+    /// The most bytes that the cache may take up. The oldest results are removed beyond it.
+    max_size: u64,
+}
+
+impl Cache {
+    pub fn new(dir: PathBuf, ttl: Duration, max_size: u64) -> Self {
+        Cache { dir, ttl, max_size }
+    }
+
+    /// Returns the key that the results of looking `word` up from `provider` are stored under.
+    pub fn key(provider: &str, options: &LookupOptions, word: &str) -> String {
+        format!(
+            "{}\t{}\t{}\t{}",
+            provider,
+            options.language,
+            options.database.as_deref().unwrap_or(""),
+            word.trim().to_lowercase()
+        )
+    }
+
+    pub fn get(&self, key: &str) -> Option<Hit> {
+        let contents = fs::read(self.path(key)).ok()?;
+        let record: Record = serde_json::from_slice(&contents).ok()?;
+        // Keys are hashed into file names, so a different key can share the file.
+        if record.key != key {
+            return None;
+        }
+        let age = now().saturating_sub(record.fetched);
+        Some(Hit { entries: record.entries, expired: age >= self.ttl.as_secs() })
+    }
+
+    /// Stores `entries` under `key`, then removes the oldest results if the cache is too large.
+    pub fn put(&self, key: &str, entries: &[Thesaurus]) -> io::Result<()> {
+        let record = Record { key: key.to_string(), fetched: now(), entries: entries.to_vec() };
+        let contents = serde_json::to_vec(&record)?;
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path(key);
+        fs::write(&path, contents)?;
+        self.prune(&path)
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{:016x}.json", fnv1a(key)))
+    }
+
+    /// Removes the least recently written files, other than `keep`, until the cache fits in `max_size`.
+    fn prune(&self, keep: &Path) -> io::Result<()> {
+        let mut files: Vec<(SystemTime, u64, PathBuf)> = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_file() {
+                files.push((metadata.modified()?, metadata.len(), entry.path()));
+            }
+        }
+        let mut size: u64 = files.iter().map(|(_, len, _)| len).sum();
+        files.sort();
+        for (_, len, path) in files {
+            if size <= self.max_size {
+                break;
+            }
+            if path != keep {
+                fs::remove_file(&path)?;
+                size -= len;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Hashes `s` with 64-bit FNV-1a, which unlike the standard library's hasher is the same
+/// from one build to the next.
+fn fnv1a(s: &str) -> u64 {
+    s.bytes().fold(0xcbf29ce484222325, |hash, byte| (hash ^ (byte as u64)).wrapping_mul(0x100000001b3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("thesaurust-cache-{}-{}", std::process::id(), name))
+    }
+
+    fn entries(word: &str) -> Vec<Thesaurus> {
+        vec![Thesaurus { word: Some(word.to_string()), ..Thesaurus::default() }]
+    }
+
+    #[test]
+    fn test_key() {
+        let options = LookupOptions::default();
+        assert_eq!("free-dictionary\ten\t\thello", Cache::key("free-dictionary", &options, " Hello"));
+    }
+
+    #[test]
+    fn test_put_and_get() {
+        let dir = temp_dir("get");
+        let cache = Cache::new(dir.clone(), Duration::from_secs(60), 1 << 20);
+        let key = Cache::key("free-dictionary", &LookupOptions::default(), "hello");
+        assert!(cache.get(&key).is_none());
+        cache.put(&key, &entries("hello")).unwrap();
+        let hit = cache.get(&key).unwrap();
+        assert_eq!(Some(String::from("hello")), hit.entries[0].word);
+        assert!(!hit.expired);
+        // Results outlive their time to live, but are marked as expired.
+        let cache = Cache::new(dir.clone(), Duration::ZERO, 1 << 20);
+        assert!(cache.get(&key).unwrap().expired);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_prune_keeps_latest() {
+        let dir = temp_dir("prune");
+        let cache = Cache::new(dir.clone(), Duration::from_secs(60), 1);
+        let options = LookupOptions::default();
+        for word in ["one", "two", "three"] {
+            cache.put(&Cache::key("test", &options, word), &entries(word)).unwrap();
+        }
+        assert_eq!(1, fs::read_dir(&dir).unwrap().count());
+        assert!(cache.get(&Cache::key("test", &options, "three")).is_some());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
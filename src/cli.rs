@@ -0,0 +1,186 @@
+use std::{ env, ffi::OsString, path::PathBuf };
+
+use structopt::{ clap::{ AppSettings, ErrorKind }, StructOpt };
+
+use crate::models::app::InputMode;
+
+/// A simple dictionary application built within the terminal.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "thesaurust")]
+pub struct Opt {
+    /// Language to look words up in, such as `en`, `es` or `de`.
+    #[structopt(short, long)]
+    pub language: Option<String>,
+
+    /// Word to look up as soon as the interface opens.
+    pub word: Option<String>,
+
+    /// Part of speech to select once the word has been looked up, such as `verb`.
+    #[structopt(long)]
+    pub pos: Option<String>,
+
+    /// Mode to start in: normal, editing, select-part-of-speech, select-definition or settings.
+    /// Defaults to select-part-of-speech when a word is given.
+    #[structopt(long)]
+    pub mode: Option<InputMode>,
+
+    #[structopt(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// The options before the word that take a value, which may be the same as the word.
+const OPTIONS_WITH_VALUES: [&str; 4] = ["-l", "--language", "--pos", "--mode"];
+
+impl Opt {
+    /// Parses the command line. clap takes a word that resembles a subcommand, such as `hello`
+    /// for `help`, to be a mistyped one; it is looked up instead.
+    pub fn from_command_line() -> Self {
+        let args: Vec<OsString> = env::args_os().collect();
+        let err = match Opt::from_iter_safe(&args) {
+            Ok(opt) => {
+                return opt;
+            }
+            Err(err) => err,
+        };
+        let word = err.info.as_ref().and_then(|info| info.first()).map(OsString::from);
+        match word {
+            Some(word) if err.kind == ErrorKind::InvalidSubcommand => {
+                // Everything after `--` is taken as a word, so the word goes last. clap still
+                // suggests subcommands after `--` unless it infers them instead.
+                let mut args = args;
+                if let Some(i) = positional_index(&args, &word) {
+                    args.remove(i);
+                }
+                if !args.iter().any(|arg| arg == "--") {
+                    args.push(OsString::from("--"));
+                }
+                args.push(word);
+                let matches = Opt::clap()
+                    .setting(AppSettings::InferSubcommands)
+                    .get_matches_from_safe(args)
+                    .unwrap_or_else(|err| err.exit());
+                Opt::from_clap(&matches)
+            }
+            _ => err.exit(),
+        }
+    }
+}
+
+/// Finds where `word` was given as the word to look up, rather than as the value of an option.
+fn positional_index(args: &[OsString], word: &OsString) -> Option<usize> {
+    (1..args.len()).find(|&i| {
+        args[i] == *word && !OPTIONS_WITH_VALUES.iter().any(|option| args[i - 1] == *option)
+    })
+}
+
+/// Parses a count that must be at least 1.
+fn parse_positive(arg: &str) -> Result<usize, String> {
+    match arg.parse::<usize>() {
+        Ok(0) => Err(String::from("must be at least 1")),
+        Ok(n) => Ok(n),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+#[derive(Debug, StructOpt)]
+pub enum Command {
+    /// Imports a kaikki.org Wiktionary JSONL extract into the local store.
+    ImportWiktionary {
+        /// The JSONL file to import.
+        #[structopt(parse(from_os_str))]
+        input: PathBuf,
+
+        /// Where to build the store. Defaults to `[wiktionary] store` in the config file.
+        #[structopt(long, parse(from_os_str))]
+        store: Option<PathBuf>,
+    },
+    /// Prints the entry for a word and exits, without entering the interface.
+    Define {
+        word: String,
+
+        /// Only print meanings with this part of speech, such as `noun` or `verb`.
+        #[structopt(long)]
+        pos: Option<String>,
+
+        /// The most definitions to print for each part of speech.
+        #[structopt(long, parse(try_from_str = parse_positive))]
+        limit: Option<usize>,
+
+        /// Prints without colors, as is done when the output is not a terminal.
+        #[structopt(long)]
+        no_color: bool,
+
+        /// Prints the entry as JSON, in the schema described in the README.
+        #[structopt(long)]
+        json: bool,
+    },
+    /// Looks up words read one per line from a file or stdin, printing a line of JSON for each.
+    Batch {
+        /// The file to read words from. Defaults to stdin.
+        #[structopt(parse(from_os_str))]
+        input: Option<PathBuf>,
+
+        /// The most words to look up at once.
+        #[structopt(short = "j", long, default_value = "4", parse(try_from_str = parse_positive))]
+        parallel: usize,
+
+        /// Only include meanings with this part of speech, such as `noun` or `verb`.
+        #[structopt(long)]
+        pos: Option<String>,
+
+        /// The most definitions to include for each part of speech.
+        #[structopt(long, parse(try_from_str = parse_positive))]
+        limit: Option<usize>,
+    },
+    /// Prints the synonyms of a word, one per line.
+    Syn(RelatedOpt),
+
+    /// Prints the antonyms of a word, one per line.
+    Ant(RelatedOpt),
+}
+
+/// Options of `syn` and `ant`.
+#[derive(Debug, StructOpt)]
+pub struct RelatedOpt {
+    pub word: String,
+
+    /// Only include meanings with this part of speech, such as `noun` or `verb`.
+    #[structopt(long)]
+    pub pos: Option<String>,
+
+    /// Only include this definition of each meaning, counting from 1.
+    #[structopt(short, long, parse(try_from_str = parse_positive))]
+    pub definition: Option<usize>,
+
+    /// Lists words that several definitions share only once.
+    #[structopt(long)]
+    pub dedupe: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn parse(args: &[&str]) -> Result<Opt, structopt::clap::Error> {
+        Opt::from_iter_safe(std::iter::once("thesaurust").chain(args.iter().copied()))
+    }
+
+    #[test]
+    fn test_positional_index() {
+        let args: Vec<OsString> = ["thesaurust", "--pos", "help", "-l", "en", "help"].map(OsString::from).to_vec();
+        assert_eq!(Some(5), positional_index(&args, &OsString::from("help")));
+        assert_eq!(None, positional_index(&args, &OsString::from("hello")));
+    }
+
+    #[test]
+    fn test_counts_must_be_positive() {
+        match parse(&["batch", "-j", "2"]).unwrap().command {
+            Some(Command::Batch { parallel, .. }) => assert_eq!(2, parallel),
+            command => panic!("unexpected command {:?}", command),
+        }
+        assert_eq!(ErrorKind::ValueValidation, parse(&["batch", "-j", "0"]).unwrap_err().kind);
+        assert_eq!(ErrorKind::ValueValidation, parse(&["syn", "happy", "-d", "0"]).unwrap_err().kind);
+        assert_eq!(ErrorKind::ValueValidation, parse(&["define", "happy", "--limit", "0"]).unwrap_err().kind);
+    }
+}
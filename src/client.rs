@@ -1,98 +1,445 @@
-use serpapi_search_rust::serp_api_search::SerpApiSearch;
+use std::{ io, sync::Arc };
 
-use crate::models::{ data::Thesaurus, errors::ApiError, word_suggestion::SearchResults };
-use crate::api_key::API_KEY;
-use std::collections::HashMap;
+use tokio::{
+    runtime::Runtime,
+    sync::{ mpsc::{ self, UnboundedReceiver, UnboundedSender }, oneshot, Semaphore },
+    task::{ AbortHandle, JoinSet },
+};
 
-const DOMAIN: &'static str = "https://api.dictionaryapi.dev/api/v2/entries/en";
+use crate::cache::{ Cache, Origin };
+use crate::http::Status;
+use crate::lemma::{ lemmas, Lemma };
+use crate::models::{ data::Thesaurus, errors::{ ApiError, ErrorReport } };
+use crate::providers::{ Database, DictionaryProvider, LookupOptions, Provider };
 
+/// The most lemmas that are looked up for a word that cannot be found.
+const MAX_LEMMA_LOOKUPS: usize = 3;
+
+#[derive(Default)]
 pub struct WordInfo {
     pub t: Vec<Thesaurus>,
     pub is_spelling_suggested: bool,
+    /// Why nothing was found.
+    pub error: Option<ErrorReport>,
+    /// Spellings to offer instead of the word, the most likely first.
+    pub suggestions: Vec<String>,
+    /// Says when the results are for another form of the word.
+    pub notice: Option<String>,
+    /// Where the results came from, when something was found.
+    pub origin: Option<Origin>,
 }
 
-pub fn parse_response(word: String, is_spelling_fix_enabled: bool) -> WordInfo {
-    match fetch_response(word, is_spelling_fix_enabled) {
-        Ok(t) => t,
-        Err(_) =>
-            WordInfo {
-                t: Thesaurus::inject_message(String::from("Unsuccessful response")),
-                is_spelling_suggested: false,
-            },
+impl WordInfo {
+    pub fn failed(error: ErrorReport) -> Self {
+        WordInfo { error: Some(error), ..WordInfo::default() }
     }
 }
 
-#[tokio::main]
-async fn fetch_response(
+/// Runs lookups on a background task of a single runtime, so that the interface keeps
+/// responding while they wait on the network.
+pub struct Lookups {
+    runtime: Runtime,
+    sender: UnboundedSender<(u64, WordInfo)>,
+    receiver: UnboundedReceiver<(u64, WordInfo)>,
+    /// The lookup that is running, the number it was started with and why it is waiting.
+    running: Option<(u64, AbortHandle, Status)>,
+    next_id: u64,
+    /// Synonyms being looked up into the cache.
+    prefetching: Option<AbortHandle>,
+    /// The databases being listed, until they are taken.
+    databases: Option<oneshot::Receiver<Vec<Database>>>,
+}
+
+impl Lookups {
+    pub fn new() -> io::Result<Self> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        Ok(Lookups {
+            runtime: Runtime::new()?,
+            sender,
+            receiver,
+            running: None,
+            next_id: 0,
+            prefetching: None,
+            databases: None,
+        })
+    }
+
+    /// Starts looking `word` up, cancelling the lookup that is running and any prefetching,
+    /// so that the word is not kept waiting behind other requests.
+    pub fn start(
+        &mut self,
+        provider: Provider,
+        cache: Option<Cache>,
+        options: LookupOptions,
+        word: String,
+        is_spelling_fix_enabled: bool
+    ) {
+        self.cancel();
+        self.stop_prefetching();
+        let id = self.next_id;
+        self.next_id += 1;
+        let sender = self.sender.clone();
+        let status = Status::default();
+        let lookup = status.clone().scope(async move {
+            let results = parse_response(&*provider, cache.as_ref(), &options, word, is_spelling_fix_enabled).await;
+            let _ = sender.send((id, results));
+        });
+        let handle = self.runtime.spawn(lookup);
+        self.running = Some((id, handle.abort_handle(), status));
+    }
+
+    /// Cancels the lookup that is running, if any.
+    pub fn cancel(&mut self) {
+        if let Some((_, handle, _)) = self.running.take() {
+            handle.abort();
+        }
+    }
+
+    /// Starts looking `words` up into `cache` in the background, at most `concurrency` at a time,
+    /// so that they are shown at once when searched for. Replaces the words being prefetched.
+    pub fn prefetch(
+        &mut self,
+        provider: Provider,
+        cache: Cache,
+        options: LookupOptions,
+        words: Vec<String>,
+        concurrency: usize
+    ) {
+        self.stop_prefetching();
+        if concurrency == 0 || words.is_empty() {
+            return;
+        }
+        let handle = self.runtime.spawn(prefetch(provider, cache, options, words, concurrency));
+        self.prefetching = Some(handle.abort_handle());
+    }
+
+    /// Starts listing the databases that `provider` can restrict lookups to.
+    pub fn list_databases(&mut self, provider: Provider) {
+        let (sender, receiver) = oneshot::channel();
+        self.runtime.spawn(async move {
+            let _ = sender.send(list_databases(&*provider).await);
+        });
+        self.databases = Some(receiver);
+    }
+
+    /// Returns the databases once they have been listed.
+    pub fn listed_databases(&mut self) -> Option<Vec<Database>> {
+        let databases = self.databases.as_mut()?.try_recv().ok()?;
+        self.databases = None;
+        Some(databases)
+    }
+
+    fn stop_prefetching(&mut self) {
+        if let Some(handle) = self.prefetching.take() {
+            handle.abort();
+        }
+    }
+
+    /// Returns why the running lookup is waiting, such as `rate limited, retrying in 4s`.
+    pub fn status(&self) -> Option<String> {
+        self.running.as_ref().and_then(|(_, _, status)| status.get())
+    }
+
+    /// Returns the results of the running lookup once it has finished. Results of cancelled
+    /// lookups that finished anyway are dropped.
+    pub fn finished(&mut self) -> Option<WordInfo> {
+        while let Ok((id, results)) = self.receiver.try_recv() {
+            if self.running.as_ref().is_some_and(|(running, _, _)| *running == id) {
+                self.running = None;
+                return Some(results);
+            }
+        }
+        None
+    }
+}
+
+/// Looks `word` up, falling back to its lemma and then to spelling suggestions if it has no entry.
+pub async fn parse_response(
+    provider: &dyn DictionaryProvider,
+    cache: Option<&Cache>,
+    options: &LookupOptions,
     word: String,
     is_spelling_fix_enabled: bool
-) -> Result<WordInfo, Box<dyn std::error::Error>> {
-    let res = match search_dictionary(word.clone()).await {
+) -> WordInfo {
+    let err = match lookup(provider, cache, options, &word).await {
+        Ok((t, origin)) => {
+            return WordInfo { t, origin: Some(origin), ..WordInfo::default() };
+        }
+        Err(err) => err,
+    };
+    if !matches!(err, ApiError::NotFound) {
+        return WordInfo::failed(err.report());
+    }
+    if let Some((t, origin, lemma)) = lookup_lemma(provider, cache, options, &word).await {
+        return WordInfo {
+            t,
+            notice: Some(format!("showing results for {} ({})", lemma.word, lemma.relation)),
+            origin: Some(origin),
+            ..WordInfo::default()
+        };
+    }
+    let not_found = ErrorReport {
+        message: format!("No entry found for \"{}\"", word.trim()),
+        ..err.report()
+    };
+    if !is_spelling_fix_enabled {
+        return WordInfo::failed(not_found);
+    }
+    match provider.suggestions(&word, options).await {
+        Ok(suggestions) if !suggestions.is_empty() => {
+            WordInfo { is_spelling_suggested: true, suggestions, ..WordInfo::default() }
+        }
+        Ok(_) => {
+            WordInfo::failed(ErrorReport {
+                hint: Some(String::from("No similar spellings were found either; check the spelling.")),
+                ..not_found
+            })
+        }
+        Err(err) => WordInfo::failed(err.report()),
+    }
+}
+
+/// Looks `word` up from the cache, or from the provider if it is not cached or has expired.
+/// Expired results are still shown when the provider cannot be reached. Local providers are
+/// fast enough that they are never cached.
+async fn lookup(
+    provider: &dyn DictionaryProvider,
+    cache: Option<&Cache>,
+    options: &LookupOptions,
+    word: &str
+) -> Result<(Vec<Thesaurus>, Origin), ApiError> {
+    if provider.is_local(options) {
+        return Ok((provider.lookup(word, options).await?, Origin::Local));
+    }
+    let cache = match cache {
+        Some(cache) => cache,
+        None => {
+            return Ok((provider.lookup(word, options).await?, Origin::Network));
+        }
+    };
+    let key = Cache::key(provider.name(), options, word);
+    let hit = cache.get(&key);
+    if let Some(hit) = hit.as_ref().filter(|hit| !hit.expired) {
+        return Ok((hit.entries.clone(), Origin::Cache));
+    }
+    match provider.lookup(word, options).await {
         Ok(t) => {
-            let resp: Vec<Thesaurus> = serde_json::from_value(t).unwrap();
-            WordInfo {
-                t: resp,
-                is_spelling_suggested: false,
-            }
+            // A cache that cannot be written to only means looking the word up again next time.
+            let _ = cache.put(&key, &t);
+            Ok((t, Origin::Network))
         }
-        Err(_) => {
-            if !is_spelling_fix_enabled {
-                WordInfo {
-                    t: Thesaurus::inject_message(
-                        String::from("Please double-check your spelling.")
-                    ),
-                    is_spelling_suggested: false,
-                }
-            } else {
-                match suggest_spelling(word).await {
-                    Ok(t) => {
-                        WordInfo {
-                            t: Thesaurus::inject_message(t),
-                            is_spelling_suggested: true,
-                        }
-                    }
-                    Err(_) => {
-                        WordInfo {
-                            t: Thesaurus::inject_message(String::from("Serp Api error")),
-                            is_spelling_suggested: false,
-                        }
-                    }
-                }
+        Err(err @ (ApiError::Network(_) | ApiError::Timeout(_) | ApiError::HttpError(_))) =>
+            match hit {
+                Some(hit) => Ok((hit.entries, Origin::Cache)),
+                None => Err(err),
             }
+        Err(err) => Err(err),
+    }
+}
+
+/// Looks up the `words` that are not freshly cached, at most `concurrency` at a time.
+/// Failures are ignored; the word is looked up again when it is searched for. Local providers
+/// are not cached, so nothing is prefetched from them.
+async fn prefetch(provider: Provider, cache: Cache, options: LookupOptions, words: Vec<String>, concurrency: usize) {
+    if provider.is_local(&options) {
+        return;
+    }
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    // Dropping the set when the prefetch is aborted aborts the lookups in it.
+    let mut lookups = JoinSet::new();
+    let mut seen: Vec<String> = Vec::new();
+    for word in words {
+        let key = Cache::key(provider.name(), &options, &word);
+        if seen.contains(&key) || cache.get(&key).is_some_and(|hit| !hit.expired) {
+            continue;
         }
-    };
-    Ok(res)
+        seen.push(key);
+        let permit = match semaphore.clone().acquire_owned().await {
+            Ok(permit) => permit,
+            Err(_) => {
+                break;
+            }
+        };
+        let (provider, cache, options) = (provider.clone(), cache.clone(), options.clone());
+        lookups.spawn(async move {
+            let _ = lookup(&*provider, Some(&cache), &options, &word).await;
+            drop(permit);
+        });
+    }
+    while lookups.join_next().await.is_some() {}
 }
 
-async fn search_dictionary(word: String) -> Result<serde_json::Value, ApiError> {
-    let url = construct_url(word);
-    let response = reqwest::get(&url).await?;
-    if response.status().is_success() {
-        let results: serde_json::Value = response.json().await?;
-        Ok(results)
-    } else {
-        Err(ApiError::InvalidInput)
+/// Looks up the lemmas that an English `word` may be an inflection of, returning the
+/// results for the first one found.
+async fn lookup_lemma(
+    provider: &dyn DictionaryProvider,
+    cache: Option<&Cache>,
+    options: &LookupOptions,
+    word: &str
+) -> Option<(Vec<Thesaurus>, Origin, Lemma)> {
+    if !options.language.starts_with("en") {
+        return None;
     }
+    for lemma in lemmas(word).into_iter().take(MAX_LEMMA_LOOKUPS) {
+        if let Ok((t, origin)) = lookup(provider, cache, options, &lemma.word).await {
+            return Some((t, origin, lemma));
+        }
+    }
+    None
+}
+
+/// Lists the databases that the provider can restrict lookups to.
+pub async fn list_databases(provider: &dyn DictionaryProvider) -> Vec<Database> {
+    provider.databases().await.unwrap_or_default()
 }
 
-async fn suggest_spelling(word: String) -> Result<String, Box<dyn std::error::Error>> {
-    let mut params = HashMap::<String, String>::new();
-    params.insert("q".to_string(), word.to_string());
-    params.insert("hl".to_string(), "en".to_string());
-    params.insert("gl".to_string(), "us".to_string());
+#[cfg(test)]
+mod tests {
+    use std::{ sync::atomic::{ AtomicBool, Ordering }, time::Duration };
 
-    let search = SerpApiSearch::google(params, API_KEY.to_string());
+    use async_trait::async_trait;
 
-    let results = search.json().await?;
-    let search_information = &results["search_information"];
+    use super::*;
+    use pretty_assertions::assert_eq;
 
-    let results: SearchResults = serde_json
-        ::from_value(search_information.clone())
-        .unwrap_or(SearchResults { spelling_fix: String::from("") });
+    /// A provider that knows every word until it is taken offline.
+    struct Flaky {
+        offline: AtomicBool,
+    }
 
-    Ok(results.spelling_fix)
-}
+    #[async_trait]
+    impl DictionaryProvider for Flaky {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        async fn lookup(&self, word: &str, _options: &LookupOptions) -> Result<Vec<Thesaurus>, ApiError> {
+            if self.offline.load(Ordering::SeqCst) {
+                return Err(ApiError::Network(String::from("offline")));
+            }
+            Ok(vec![Thesaurus { word: Some(word.to_string()), ..Thesaurus::default() }])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lookup_from_cache() {
+        let dir = std::env::temp_dir().join(format!("thesaurust-client-{}", std::process::id()));
+        let provider = Flaky { offline: AtomicBool::new(false) };
+        let options = LookupOptions::default();
+        let cache = Cache::new(dir.clone(), Duration::from_secs(60), 1 << 20);
+        let (_, origin) = lookup(&provider, Some(&cache), &options, "hello").await.unwrap();
+        assert_eq!(Origin::Network, origin);
+        let (_, origin) = lookup(&provider, Some(&cache), &options, "hello").await.unwrap();
+        assert_eq!(Origin::Cache, origin);
+
+        // Expired results are shown when the provider cannot be reached.
+        provider.offline.store(true, Ordering::SeqCst);
+        let expired = Cache::new(dir.clone(), Duration::ZERO, 1 << 20);
+        let (t, origin) = lookup(&provider, Some(&expired), &options, "hello").await.unwrap();
+        assert_eq!(Origin::Cache, origin);
+        assert_eq!(Some(String::from("hello")), t[0].word);
+        assert!(lookup(&provider, Some(&expired), &options, "other").await.is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    /// A provider that reads words from files on this machine.
+    struct Local;
+
+    #[async_trait]
+    impl DictionaryProvider for Local {
+        fn name(&self) -> &str {
+            "local"
+        }
+
+        fn is_local(&self, _options: &LookupOptions) -> bool {
+            true
+        }
+
+        async fn lookup(&self, word: &str, _options: &LookupOptions) -> Result<Vec<Thesaurus>, ApiError> {
+            Ok(vec![Thesaurus { word: Some(word.to_string()), ..Thesaurus::default() }])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_local_lookups_are_not_cached() {
+        let dir = std::env::temp_dir().join(format!("thesaurust-local-{}", std::process::id()));
+        let options = LookupOptions::default();
+        let cache = Cache::new(dir.clone(), Duration::from_secs(60), 1 << 20);
+        let (_, origin) = lookup(&Local, Some(&cache), &options, "hello").await.unwrap();
+        assert_eq!(Origin::Local, origin);
+        assert!(cache.get(&Cache::key("local", &options, "hello")).is_none());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    /// A provider without any entries.
+    struct Empty;
+
+    #[async_trait]
+    impl DictionaryProvider for Empty {
+        fn name(&self) -> &str {
+            "empty"
+        }
+
+        async fn lookup(&self, _word: &str, _options: &LookupOptions) -> Result<Vec<Thesaurus>, ApiError> {
+            Err(ApiError::NotFound)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_reported() {
+        let options = LookupOptions::default();
+        let results = parse_response(&Empty, None, &options, String::from("zzz"), false).await;
+        let error = results.error.unwrap();
+        assert_eq!("Not found", error.title);
+        assert_eq!("No entry found for \"zzz\"", error.message);
+        assert!(results.t.is_empty());
 
-fn construct_url(word: String) -> String {
-    format!("{}/{}", DOMAIN, word)
+        let provider = Flaky { offline: AtomicBool::new(true) };
+        let results = parse_response(&provider, None, &options, String::from("hello"), false).await;
+        assert_eq!("Network error", results.error.unwrap().title);
+    }
+
+    fn wait_for(lookups: &mut Lookups) -> Option<WordInfo> {
+        for _ in 0..50 {
+            if let Some(results) = lookups.finished() {
+                return Some(results);
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        None
+    }
+
+    #[test]
+    fn test_lookups_deliver_results() {
+        let mut lookups = Lookups::new().unwrap();
+        let provider = Provider::new(Flaky { offline: AtomicBool::new(false) });
+        lookups.start(provider.clone(), None, LookupOptions::default(), String::from("hello"), false);
+        let results = wait_for(&mut lookups).unwrap();
+        assert_eq!(Some(String::from("hello")), results.t[0].word);
+
+        // Cancelled lookups are never delivered.
+        lookups.start(provider, None, LookupOptions::default(), String::from("bye"), false);
+        lookups.cancel();
+        assert!(wait_for(&mut lookups).is_none());
+    }
+
+    #[test]
+    fn test_prefetch_fills_cache() {
+        let dir = std::env::temp_dir().join(format!("thesaurust-prefetch-{}", std::process::id()));
+        let cache = Cache::new(dir.clone(), Duration::from_secs(60), 1 << 20);
+        let options = LookupOptions::default();
+        let mut lookups = Lookups::new().unwrap();
+        let provider = Provider::new(Flaky { offline: AtomicBool::new(false) });
+        let words = vec![String::from("glad"), String::from("content"), String::from("Glad")];
+        lookups.prefetch(provider, cache.clone(), options.clone(), words, 2);
+        let cached = |word| cache.get(&Cache::key("flaky", &options, word)).is_some();
+        for _ in 0..50 {
+            if cached("glad") && cached("content") {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(cached("glad") && cached("content"));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
 }
@@ -0,0 +1,17 @@
+use ratatui::{
+    style::{Color, Style},
+    widgets::{Block, Borders, List, ListItem},
+};
+
+use crate::models::app::App;
+
+pub fn new(app: &App) -> List<'_> {
+    let antonyms: Vec<ListItem> = app
+        .antonyms
+        .iter()
+        .map(|i| ListItem::new(i.clone()))
+        .collect();
+    List::new(antonyms)
+        .block(Block::default().borders(Borders::ALL).title("Antonyms"))
+        .style(Style::default().fg(Color::Green))
+}
@@ -0,0 +1,62 @@
+use ratatui::{
+    widgets::{ Block, Borders, Paragraph, Wrap },
+    style::{ Color, Style },
+    layout::Alignment,
+    text::Line,
+};
+
+use crate::models::data::{ License, Thesaurus };
+
+/// Shows how the word is pronounced and where its entry came from, after `notice` if there is one.
+pub fn new(thesaurus: &Thesaurus, notice: Option<&str>) -> Paragraph<'static> {
+    let mut text: Vec<Line> = Vec::new();
+    if let Some(notice) = notice {
+        text.push(Line::styled(notice.to_string(), Style::default().fg(Color::Yellow)));
+    }
+    text.extend(lines(thesaurus).into_iter().map(Line::from));
+    Paragraph::new(text)
+        .alignment(Alignment::Left)
+        .style(Style::default().fg(Color::DarkGray))
+        .wrap(Wrap { trim: true })
+        .block(Block::default().borders(Borders::NONE))
+}
+
+fn lines(thesaurus: &Thesaurus) -> Vec<String> {
+    let mut lines = Vec::new();
+    let phonetics = thesaurus.phonetics.clone().unwrap_or_default();
+    let phonetic = thesaurus.phonetic
+        .clone()
+        .or_else(|| phonetics.iter().find_map(|p| p.text.clone()));
+    if let Some(phonetic) = phonetic {
+        lines.push(format!("{} {}", thesaurus.word.clone().unwrap_or_default(), phonetic));
+    }
+    if let Some(recording) = phonetics.iter().find(|p| p.audio.as_ref().is_some_and(|a| !a.is_empty())) {
+        let mut line = format!("Audio: {}", recording.audio.clone().unwrap_or_default());
+        let credits: Vec<String> = recording.license
+            .as_ref()
+            .and_then(describe)
+            .into_iter()
+            .chain(recording.sourceUrl.clone())
+            .collect();
+        if !credits.is_empty() {
+            line.push_str(&format!(" ({})", credits.join(", ")));
+        }
+        lines.push(line);
+    }
+    if let Some(urls) = thesaurus.sourceUrls.as_ref().filter(|u| !u.is_empty()) {
+        lines.push(format!("Source: {}", urls.join(", ")));
+    }
+    if let Some(license) = thesaurus.license.as_ref().and_then(describe) {
+        lines.push(format!("License: {}", license));
+    }
+    lines
+}
+
+fn describe(license: &License) -> Option<String> {
+    match (&license.name, &license.url) {
+        (Some(name), Some(url)) => Some(format!("{} <{}>", name, url)),
+        (Some(name), None) => Some(name.clone()),
+        (None, Some(url)) => Some(url.clone()),
+        (None, None) => None,
+    }
+}
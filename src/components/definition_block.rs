@@ -7,16 +7,21 @@ use ratatui::{
     widgets::{Block, Borders, Paragraph, Wrap},
 };
 
-pub fn new(app: &mut App, definitions: Vec<Definition>, definition: String) -> Paragraph {
-    Paragraph::new(String::from(definition))
+pub fn new(app: &mut App, definitions: Vec<Definition>, definition: String) -> Paragraph<'_> {
+    Paragraph::new(definition)
         .style(match app.input_mode {
             InputMode::SelectDefinition => Style::default().fg(Color::Yellow),
             _ => Style::default().fg(Color::Green),
         })
         .wrap(Wrap { trim: true })
-        .block(Block::default().borders(Borders::ALL).title(format!(
-            "Definition[{:}/{}]",
-            app.definition_list.state.selected().unwrap() + 1,
-            definitions.len()
-        )))
+        .block(Block::default().borders(Borders::ALL).title(title(app, &definitions)))
+}
+
+fn title(app: &App, definitions: &[Definition]) -> String {
+    let idx = app.definition_list.state.selected().unwrap();
+    let title = format!("Definition[{:}/{}]", idx + 1, definitions.len());
+    match definitions.get(idx).and_then(|d| d.source.as_ref()) {
+        Some(source) => format!("{} · {}", title, source),
+        None => title,
+    }
 }
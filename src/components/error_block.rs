@@ -0,0 +1,20 @@
+use ratatui::{
+    widgets::{ Block, Borders, Paragraph, Wrap },
+    style::{ Color, Style },
+    text::Line,
+};
+
+use crate::models::errors::ErrorReport;
+
+/// Shows why the last lookup failed and what can be done about it.
+pub fn new(error: &ErrorReport) -> Paragraph<'static> {
+    let mut text = vec![Line::from(error.message.clone())];
+    if let Some(hint) = &error.hint {
+        text.push(Line::from(""));
+        text.push(Line::styled(hint.clone(), Style::default().fg(Color::Yellow)));
+    }
+    Paragraph::new(text)
+        .style(Style::default().fg(Color::Red))
+        .wrap(Wrap { trim: true })
+        .block(Block::default().borders(Borders::ALL).title(error.title.clone()))
+}
@@ -1,15 +1,24 @@
-use ratatui::{ widgets::{ Block, Borders, Paragraph }, style::{ Color, Style }, layout::Alignment };
+use ratatui::{
+    widgets::{ Block, Borders, Paragraph },
+    style::{ Color, Style },
+    layout::Alignment,
+};
 
-pub fn with(instructions: &str) -> Paragraph {
+pub fn with(instructions: &str) -> Paragraph<'_> {
     match instructions {
         "default" => block_with("q: Quit"),
         _ => block_with(instructions),
     }
 }
 
-fn block_with(s: &str) -> Paragraph {
+fn block_with(s: &str) -> Paragraph<'_> {
     Paragraph::new(s)
         .alignment(Alignment::Left)
         .style(Style::default().fg(Color::Green))
         .block(Block::default().borders(Borders::NONE))
 }
+
+/// Shows why the running lookup is waiting.
+pub fn status(message: &str) -> Paragraph<'_> {
+    block_with(message).style(Style::default().fg(Color::Yellow))
+}
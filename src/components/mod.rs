@@ -6,3 +6,6 @@ pub mod part_of_speech_block;
 pub mod footer;
 pub mod popup;
 pub mod synonym_block;
+pub mod antonym_block;
+pub mod attribution;
+pub mod error_block;
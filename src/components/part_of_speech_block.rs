@@ -2,7 +2,7 @@ use ratatui::{ widgets::{ Block, Borders, List, ListItem }, style::{ Color, Styl
 
 use crate::models::app::{ App, InputMode };
 
-pub fn new(app: &mut App) -> List {
+pub fn new(app: &mut App) -> List<'_> {
     let cloned_list = app.part_of_speech_list.clone();
     let parts_of_speech: Vec<ListItem> = cloned_list.items
         .iter()
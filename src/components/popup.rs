@@ -17,10 +17,18 @@ pub fn new(app: &mut App) -> Paragraph<'static> {
 fn popup_message(app: &mut App) -> String {
     let message = app.suggested_spelling.clone();
     if app.is_spelling_fix_enabled {
-        if message.len() == 0 {
+        if message.is_empty() {
             return String::from("Similar spelling not found.");
         }
+        let count = app.suggestions.len();
+        if count > 1 {
+            let position = app.suggestions
+                .iter()
+                .position(|s| *s == message)
+                .unwrap_or(0);
+            return format!("Did you mean {}? ({}/{})", message, position + 1, count);
+        }
         return format!("Did you mean {}?", message);
     }
-    return message
+    message
 }
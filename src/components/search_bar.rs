@@ -1,12 +1,54 @@
 use ratatui::{ widgets::{ Block, Borders, Paragraph, Wrap }, style::{ Color, Style } };
-use crate::{ models::{ app::{ InputMode, App } } };
+use crate::{ cache::Origin, models::{ app::{ InputMode, App, Setting } } };
 
-pub fn new(app: &mut App) -> Paragraph {
+/// Frames of the spinner shown while a lookup runs.
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+pub fn new(app: &mut App) -> Paragraph<'_> {
+    if let InputMode::EnteringKey = app.input_mode {
+        return key_bar(app);
+    }
     Paragraph::new(app.input.value())
         .style(match app.input_mode {
             InputMode::Editing => Style::default().fg(Color::Yellow),
             _ => Style::default().fg(Color::Green),
         })
         .wrap(Wrap { trim: true })
-        .block(Block::default().borders(Borders::ALL).title("Search"))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title(app))
+        )
+}
+
+fn title(app: &App) -> String {
+    if app.lookup.is_some() {
+        return format!(
+            "Search ({}) {} Looking up…",
+            app.lookup_options.language,
+            SPINNER[app.spinner % SPINNER.len()]
+        );
+    }
+    let mut title = format!("Search ({})", app.lookup_options.language);
+    match app.origin {
+        Some(Origin::Cache) => title.push_str(" · from cache"),
+        Some(Origin::Network) => title.push_str(" · from network"),
+        Some(Origin::Local) => title.push_str(" · from local dictionary"),
+        None => {}
+    }
+    match app.results.len() {
+        0 | 1 => title,
+        count => format!("{} · Entry {}/{}", title, app.entry_index + 1, count),
+    }
+}
+
+/// Shows the API key being typed, hiding its characters.
+fn key_bar(app: &App) -> Paragraph<'static> {
+    let title = match app.setting {
+        Setting::ApiKey(idx) => format!("{} key", app.api_keys[idx]),
+        _ => String::from("API key"),
+    };
+    Paragraph::new("*".repeat(app.key_input.value().chars().count()))
+        .style(Style::default().fg(Color::Yellow))
+        .block(Block::default().borders(Borders::ALL).title(title))
 }
@@ -5,7 +5,7 @@ use ratatui::{
 
 use crate::models::app::App;
 
-pub fn new(app: &mut App) -> List {
+pub fn new(app: &mut App) -> List<'_> {
     let cloned_list = app.synonym_list.clone();
     let synonyms: Vec<ListItem> = cloned_list
         .items
@@ -0,0 +1,360 @@
+//! The HTTP client that every request to a web service goes through.
+
+use std::{ env, fs, future::Future, path::Path, sync::{ Arc, Mutex }, time::Duration };
+
+use reqwest::{
+    header::RETRY_AFTER,
+    Certificate,
+    Client,
+    ClientBuilder,
+    Identity,
+    NoProxy,
+    Proxy,
+    Response,
+    StatusCode,
+};
+
+use crate::api_key;
+use crate::config::HttpConfig;
+use crate::models::errors::ApiError;
+
+/// How long to wait before the first retry. Each retry after it waits twice as long.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// The secret that unlocks a PKCS #12 client certificate.
+const CLIENT_CERT: &str = "client-cert";
+
+/// The longest wait between retries. A server that asks for a longer one with `Retry-After`
+/// is not retried.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+tokio::task_local! {
+    /// Where the requests of the lookup running on the current task say why they are waiting.
+    static STATUS: Status;
+}
+
+/// Why the requests of one lookup are waiting to be retried, such as `rate limited, retrying in 4s`.
+/// Each lookup has its own, so that requests made in the background do not show or clear it.
+#[derive(Clone, Debug, Default)]
+pub struct Status(Arc<Mutex<Option<String>>>);
+
+impl Status {
+    pub fn get(&self) -> Option<String> {
+        self.0.lock().ok()?.clone()
+    }
+
+    /// Runs `future` with its requests reporting to this status.
+    pub fn scope<F: Future>(self, future: F) -> impl Future<Output = F::Output> {
+        STATUS.scope(self, future)
+    }
+
+    fn set(&self, status: Option<String>) {
+        if let Ok(mut current) = self.0.lock() {
+            *current = status;
+        }
+    }
+}
+
+/// A client with timeouts that retries requests which failed for reasons that may pass,
+/// such as a dropped connection, a busy server or being rate limited.
+#[derive(Clone, Debug)]
+pub struct Http {
+    client: Client,
+    max_retries: u32,
+}
+
+impl Http {
+    /// Builds a client with the timeouts, proxies and certificates in `config`. The proxy of
+    /// a scheme left out of it is taken from `$HTTP_PROXY` or `$HTTPS_PROXY`, and hosts are
+    /// taken from `$NO_PROXY` unless `no_proxy` is set.
+    pub fn new(config: &HttpConfig) -> Result<Self, ApiError> {
+        let builder = Client::builder()
+            .connect_timeout(Duration::from_secs(config.connect_timeout_secs))
+            .timeout(Duration::from_secs(config.timeout_secs));
+        let builder = with_certificates(with_proxies(builder, config)?, config)?;
+        let client = builder
+            .build()
+            .map_err(|err| ApiError::ConfigError(format!("Cannot set up the HTTP client: {}", err)))?;
+        Ok(Http { client, max_retries: config.max_retries })
+    }
+
+    /// Sends a GET request to `url` with `query` appended, retrying it with exponential backoff.
+    /// Responses with other error statuses are returned for the caller to handle. While a retry
+    /// waits, the reason is shown in the status of the lookup that made the request, if any.
+    pub async fn get(&self, url: &str, query: &[(&str, &str)]) -> Result<Response, ApiError> {
+        let mut status = StatusGuard { status: STATUS.try_with(Status::clone).ok(), shown: None };
+        let mut attempt = 0;
+        loop {
+            let result = self.client.get(url).query(query).send().await;
+            let (reason, delay) = match retry_reason(&result, attempt) {
+                Some(retry) => retry,
+                None => {
+                    return Ok(result?);
+                }
+            };
+            if delay > MAX_BACKOFF {
+                return Err(ApiError::RateLimited(host(url), delay));
+            }
+            if attempt >= self.max_retries {
+                return match result {
+                    Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+                        Err(ApiError::QuotaExceeded(host(url)))
+                    }
+                    result => Ok(result?),
+                };
+            }
+            status.show(format!("{}, retrying in {}s", reason, delay.as_secs_f32().ceil()));
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+impl Default for Http {
+    fn default() -> Self {
+        let config = HttpConfig::default();
+        Http::new(&config).unwrap_or_else(|_| Http { client: Client::new(), max_retries: config.max_retries })
+    }
+}
+
+/// Clears what a request showed in its status once it is done with, including when its
+/// lookup is cancelled. Leaves the status alone if another request has replaced it since.
+struct StatusGuard {
+    status: Option<Status>,
+    shown: Option<String>,
+}
+
+impl StatusGuard {
+    fn show(&mut self, message: String) {
+        if let Some(status) = &self.status {
+            status.set(Some(message.clone()));
+            self.shown = Some(message);
+        }
+    }
+}
+
+impl Drop for StatusGuard {
+    fn drop(&mut self) {
+        if let (Some(status), Some(shown)) = (&self.status, &self.shown) {
+            if status.get().as_ref() == Some(shown) {
+                status.set(None);
+            }
+        }
+    }
+}
+
+/// Adds the proxies in `config`. Without any proxy settings, reqwest finds them itself.
+fn with_proxies(mut builder: ClientBuilder, config: &HttpConfig) -> Result<ClientBuilder, ApiError> {
+    if config.http_proxy.is_none() && config.https_proxy.is_none() && config.no_proxy.is_none() {
+        return Ok(builder);
+    }
+    // Adding any proxy turns off the ones from the environment, so they are added here too.
+    for proxy in proxies(config, env_var)? {
+        builder = builder.proxy(proxy);
+    }
+    Ok(builder)
+}
+
+/// Returns the proxy of each scheme, from `config` or else from the `env` variable, with
+/// the hosts that skip them.
+fn proxies(config: &HttpConfig, env: impl Fn(&str) -> Option<String>) -> Result<Vec<Proxy>, ApiError> {
+    let no_proxy = config.no_proxy.clone().or_else(|| env("NO_PROXY"));
+    let no_proxy = || no_proxy.as_deref().and_then(NoProxy::from_string);
+    let invalid = |url: &str, err: reqwest::Error| {
+        ApiError::ConfigError(format!("Invalid proxy {}: {}", url, err))
+    };
+    let mut proxies = Vec::new();
+    if let Some(url) = config.http_proxy.clone().or_else(|| env("HTTP_PROXY")) {
+        proxies.push(Proxy::http(&url).map_err(|err| invalid(&url, err))?.no_proxy(no_proxy()));
+    }
+    if let Some(url) = config.https_proxy.clone().or_else(|| env("HTTPS_PROXY")) {
+        proxies.push(Proxy::https(&url).map_err(|err| invalid(&url, err))?.no_proxy(no_proxy()));
+    }
+    Ok(proxies)
+}
+
+/// Reads an environment variable by its upper or lower case name, ignoring it if empty.
+fn env_var(name: &str) -> Option<String> {
+    [name.to_string(), name.to_lowercase()]
+        .iter()
+        .find_map(|name| env::var(name).ok().filter(|value| !value.is_empty()))
+}
+
+/// Adds the root certificates and the client certificate in `config`.
+fn with_certificates(mut builder: ClientBuilder, config: &HttpConfig) -> Result<ClientBuilder, ApiError> {
+    if let Some(path) = &config.ca_bundle {
+        let certificates = Certificate::from_pem_bundle(&read(path)?).map_err(|err| {
+            ApiError::ConfigError(format!("Invalid CA bundle {}: {}", path.display(), err))
+        })?;
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+    if let Some(path) = &config.client_cert {
+        let identity = match &config.client_key {
+            Some(key) => Identity::from_pkcs8_pem(&read(path)?, &read(key)?),
+            None => {
+                let password = api_key::get(CLIENT_CERT)?.unwrap_or_default();
+                Identity::from_pkcs12_der(&read(path)?, &password)
+            }
+        };
+        let identity = identity.map_err(|err| {
+            ApiError::ConfigError(format!("Invalid client certificate {}: {}", path.display(), err))
+        })?;
+        builder = builder.identity(identity);
+    }
+    Ok(builder)
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, ApiError> {
+    fs::read(path).map_err(|err| ApiError::ConfigError(format!("Cannot read {}: {}", path.display(), err)))
+}
+
+/// Returns why a request that failed in a way that may pass should be retried, and how long
+/// to wait first. The wait that a server asks for is kept as it is, however long.
+fn retry_reason(result: &Result<Response, reqwest::Error>, attempt: u32) -> Option<(&'static str, Duration)> {
+    match result {
+        Ok(response) if response.status() == StatusCode::TOO_MANY_REQUESTS => {
+            Some(("rate limited", retry_after(response).unwrap_or_else(|| backoff(attempt))))
+        }
+        Ok(response) if is_transient(response.status()) => Some(("server busy", backoff(attempt))),
+        Err(err) if err.is_connect() || err.is_timeout() => Some(("connection failed", backoff(attempt))),
+        _ => None,
+    }
+}
+
+fn is_transient(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+fn backoff(attempt: u32) -> Duration {
+    INITIAL_BACKOFF.saturating_mul(2u32.saturating_pow(attempt)).min(MAX_BACKOFF)
+}
+
+/// Reads how long the server asked to wait from `Retry-After`. Only a number of seconds
+/// is understood; a date falls back to the usual backoff.
+fn retry_after(response: &Response) -> Option<Duration> {
+    let seconds = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim().parse().ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+fn host(url: &str) -> String {
+    reqwest::Url
+        ::parse(url)
+        .ok()
+        .and_then(|url| url.host_str().map(String::from))
+        .unwrap_or_else(|| url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(Duration::from_millis(500), backoff(0));
+        assert_eq!(Duration::from_secs(2), backoff(2));
+        assert_eq!(MAX_BACKOFF, backoff(10));
+    }
+
+    #[tokio::test]
+    async fn test_retries_when_rate_limited() {
+        let mut server = mockito::Server::new_async().await;
+        let limited = server
+            .mock("GET", "/word")
+            .with_status(429)
+            .with_header("retry-after", "0")
+            .expect(1)
+            .create_async().await;
+        let ok = server.mock("GET", "/word").with_body("found").create_async().await;
+        let http = Http::default();
+        let status = Status::default();
+        let response = status.clone().scope(http.get(&format!("{}/word", server.url()), &[])).await.unwrap();
+        limited.assert_async().await;
+        ok.assert_async().await;
+        assert_eq!("found", response.text().await.unwrap());
+        assert_eq!(None, status.get());
+    }
+
+    #[tokio::test]
+    async fn test_long_retry_after_is_not_retried() {
+        let mut server = mockito::Server::new_async().await;
+        let limited = server
+            .mock("GET", "/word")
+            .with_status(429)
+            .with_header("retry-after", "120")
+            .expect(1)
+            .create_async().await;
+        let result = Http::default().get(&format!("{}/word", server.url()), &[]).await;
+        limited.assert_async().await;
+        let err = result.unwrap_err();
+        assert!(matches!(err, ApiError::RateLimited(_, wait) if wait == Duration::from_secs(120)));
+        assert_eq!(Some(String::from("Wait 120s before searching again.")), err.hint());
+    }
+
+    #[test]
+    fn test_proxies_from_environment_are_kept() {
+        let config = HttpConfig { http_proxy: Some(String::from("http://config:3128")), ..HttpConfig::default() };
+        let env = |name: &str| {
+            match name {
+                "HTTP_PROXY" => Some(String::from("http://env-http:8080")),
+                "HTTPS_PROXY" => Some(String::from("http://env-https:8080")),
+                _ => None,
+            }
+        };
+        let proxies = format!("{:?}", proxies(&config, env).unwrap());
+        assert!(proxies.contains("config:3128"));
+        assert!(proxies.contains("env-https:8080"));
+        assert!(!proxies.contains("env-http:8080"));
+        assert!(Http::new(&config).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_status_of_other_lookups_is_kept() {
+        let status = Status::default();
+        status.set(Some(String::from("rate limited, retrying in 4s")));
+        // A request that shows nothing, such as one made in the background, does not clear it.
+        drop(StatusGuard { status: Some(status.clone()), shown: None });
+        drop(StatusGuard { status: Some(status.clone()), shown: Some(String::from("server busy, retrying in 1s")) });
+        assert_eq!(Some(String::from("rate limited, retrying in 4s")), status.get());
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_when_rate_limited() {
+        let mut server = mockito::Server::new_async().await;
+        let limited = server
+            .mock("GET", "/word")
+            .with_status(429)
+            .with_header("retry-after", "0")
+            .expect(2)
+            .create_async().await;
+        let http = Http::new(&HttpConfig { max_retries: 1, ..HttpConfig::default() }).unwrap();
+        let result = http.get(&format!("{}/word", server.url()), &[]).await;
+        limited.assert_async().await;
+        assert!(matches!(result, Err(ApiError::QuotaExceeded(host)) if host == "127.0.0.1"));
+    }
+
+    #[tokio::test]
+    async fn test_requests_go_through_proxy() {
+        let mut server = mockito::Server::new_async().await;
+        let proxied = server.mock("GET", "/word").with_body("proxied").create_async().await;
+        let config = HttpConfig { http_proxy: Some(server.url()), ..HttpConfig::default() };
+        let http = Http::new(&config).unwrap();
+        let response = http.get("http://dictionary.invalid/word", &[]).await.unwrap();
+        proxied.assert_async().await;
+        assert_eq!("proxied", response.text().await.unwrap());
+    }
+
+    #[test]
+    fn test_invalid_certificates() {
+        let config = HttpConfig {
+            ca_bundle: Some(std::env::temp_dir().join("thesaurust-missing-ca.pem")),
+            ..HttpConfig::default()
+        };
+        assert!(matches!(Http::new(&config), Err(ApiError::ConfigError(_))));
+    }
+}
@@ -0,0 +1,258 @@
+//! Finds the dictionary forms of inflected English words, such as "goose" for "geese".
+
+/// A dictionary form that a word may be an inflection of.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lemma {
+    pub word: String,
+    /// How the word relates to the lemma, such as `plural of`.
+    pub relation: &'static str,
+}
+
+const PLURAL: &str = "plural of";
+const PAST_TENSE: &str = "past tense of";
+const PAST_PARTICIPLE: &str = "past participle of";
+const PRESENT_PARTICIPLE: &str = "present participle of";
+const COMPARATIVE: &str = "comparative of";
+const SUPERLATIVE: &str = "superlative of";
+
+/// Forms that suffix rules get wrong.
+const IRREGULAR: &[(&str, &str, &str)] = &[
+    ("geese", "goose", PLURAL),
+    ("mice", "mouse", PLURAL),
+    ("lice", "louse", PLURAL),
+    ("children", "child", PLURAL),
+    ("men", "man", PLURAL),
+    ("women", "woman", PLURAL),
+    ("feet", "foot", PLURAL),
+    ("teeth", "tooth", PLURAL),
+    ("people", "person", PLURAL),
+    ("oxen", "ox", PLURAL),
+    ("dice", "die", PLURAL),
+    ("criteria", "criterion", PLURAL),
+    ("phenomena", "phenomenon", PLURAL),
+    ("cacti", "cactus", PLURAL),
+    ("fungi", "fungus", PLURAL),
+    ("nuclei", "nucleus", PLURAL),
+    ("stimuli", "stimulus", PLURAL),
+    ("analyses", "analysis", PLURAL),
+    ("crises", "crisis", PLURAL),
+    ("theses", "thesis", PLURAL),
+    ("data", "datum", PLURAL),
+    ("indices", "index", PLURAL),
+    ("was", "be", PAST_TENSE),
+    ("were", "be", PAST_TENSE),
+    ("been", "be", PAST_PARTICIPLE),
+    ("had", "have", PAST_TENSE),
+    ("did", "do", PAST_TENSE),
+    ("done", "do", PAST_PARTICIPLE),
+    ("went", "go", PAST_TENSE),
+    ("gone", "go", PAST_PARTICIPLE),
+    ("ran", "run", PAST_TENSE),
+    ("saw", "see", PAST_TENSE),
+    ("seen", "see", PAST_PARTICIPLE),
+    ("ate", "eat", PAST_TENSE),
+    ("eaten", "eat", PAST_PARTICIPLE),
+    ("took", "take", PAST_TENSE),
+    ("taken", "take", PAST_PARTICIPLE),
+    ("gave", "give", PAST_TENSE),
+    ("given", "give", PAST_PARTICIPLE),
+    ("came", "come", PAST_TENSE),
+    ("began", "begin", PAST_TENSE),
+    ("begun", "begin", PAST_PARTICIPLE),
+    ("knew", "know", PAST_TENSE),
+    ("known", "know", PAST_PARTICIPLE),
+    ("wrote", "write", PAST_TENSE),
+    ("written", "write", PAST_PARTICIPLE),
+    ("spoke", "speak", PAST_TENSE),
+    ("spoken", "speak", PAST_PARTICIPLE),
+    ("broke", "break", PAST_TENSE),
+    ("broken", "break", PAST_PARTICIPLE),
+    ("chose", "choose", PAST_TENSE),
+    ("chosen", "choose", PAST_PARTICIPLE),
+    ("drove", "drive", PAST_TENSE),
+    ("driven", "drive", PAST_PARTICIPLE),
+    ("flew", "fly", PAST_TENSE),
+    ("flown", "fly", PAST_PARTICIPLE),
+    ("forgot", "forget", PAST_TENSE),
+    ("forgotten", "forget", PAST_PARTICIPLE),
+    ("got", "get", PAST_TENSE),
+    ("gotten", "get", PAST_PARTICIPLE),
+    ("grew", "grow", PAST_TENSE),
+    ("grown", "grow", PAST_PARTICIPLE),
+    ("threw", "throw", PAST_TENSE),
+    ("thrown", "throw", PAST_PARTICIPLE),
+    ("wore", "wear", PAST_TENSE),
+    ("worn", "wear", PAST_PARTICIPLE),
+    ("sang", "sing", PAST_TENSE),
+    ("sung", "sing", PAST_PARTICIPLE),
+    ("swam", "swim", PAST_TENSE),
+    ("swum", "swim", PAST_PARTICIPLE),
+    ("drank", "drink", PAST_TENSE),
+    ("drunk", "drink", PAST_PARTICIPLE),
+    ("rang", "ring", PAST_TENSE),
+    ("rung", "ring", PAST_PARTICIPLE),
+    ("drew", "draw", PAST_TENSE),
+    ("drawn", "draw", PAST_PARTICIPLE),
+    ("blew", "blow", PAST_TENSE),
+    ("blown", "blow", PAST_PARTICIPLE),
+    ("fell", "fall", PAST_TENSE),
+    ("fallen", "fall", PAST_PARTICIPLE),
+    ("hid", "hide", PAST_TENSE),
+    ("hidden", "hide", PAST_PARTICIPLE),
+    ("rode", "ride", PAST_TENSE),
+    ("ridden", "ride", PAST_PARTICIPLE),
+    ("rose", "rise", PAST_TENSE),
+    ("risen", "rise", PAST_PARTICIPLE),
+    ("shook", "shake", PAST_TENSE),
+    ("shaken", "shake", PAST_PARTICIPLE),
+    ("stole", "steal", PAST_TENSE),
+    ("stolen", "steal", PAST_PARTICIPLE),
+    ("froze", "freeze", PAST_TENSE),
+    ("frozen", "freeze", PAST_PARTICIPLE),
+    ("sat", "sit", PAST_TENSE),
+    ("stood", "stand", PAST_TENSE),
+    ("told", "tell", PAST_TENSE),
+    ("sold", "sell", PAST_TENSE),
+    ("thought", "think", PAST_TENSE),
+    ("brought", "bring", PAST_TENSE),
+    ("bought", "buy", PAST_TENSE),
+    ("caught", "catch", PAST_TENSE),
+    ("taught", "teach", PAST_TENSE),
+    ("fought", "fight", PAST_TENSE),
+    ("sought", "seek", PAST_TENSE),
+    ("felt", "feel", PAST_TENSE),
+    ("kept", "keep", PAST_TENSE),
+    ("slept", "sleep", PAST_TENSE),
+    ("left", "leave", PAST_TENSE),
+    ("meant", "mean", PAST_TENSE),
+    ("met", "meet", PAST_TENSE),
+    ("paid", "pay", PAST_TENSE),
+    ("said", "say", PAST_TENSE),
+    ("made", "make", PAST_TENSE),
+    ("found", "find", PAST_TENSE),
+    ("held", "hold", PAST_TENSE),
+    ("heard", "hear", PAST_TENSE),
+    ("led", "lead", PAST_TENSE),
+    ("lost", "lose", PAST_TENSE),
+    ("sent", "send", PAST_TENSE),
+    ("spent", "spend", PAST_TENSE),
+    ("built", "build", PAST_TENSE),
+    ("won", "win", PAST_TENSE),
+    ("better", "good", COMPARATIVE),
+    ("best", "good", SUPERLATIVE),
+    ("worse", "bad", COMPARATIVE),
+    ("worst", "bad", SUPERLATIVE),
+    ("further", "far", COMPARATIVE),
+    ("furthest", "far", SUPERLATIVE),
+    ("farther", "far", COMPARATIVE),
+];
+
+/// Suffixes and what replaces them in the lemma, tried in order.
+const SUFFIXES: &[(&str, &str, &str)] = &[
+    ("ies", "y", PLURAL),
+    ("ves", "f", PLURAL),
+    ("ves", "fe", PLURAL),
+    ("ches", "ch", PLURAL),
+    ("shes", "sh", PLURAL),
+    ("sses", "ss", PLURAL),
+    ("xes", "x", PLURAL),
+    ("zes", "z", PLURAL),
+    ("oes", "o", PLURAL),
+    ("s", "", PLURAL),
+    // A silent "e" is restored first, since dropping it usually leaves another word, as
+    // "hoped" would be "hop". Without it, a lemma that does not exist is tried next.
+    ("ied", "y", PAST_TENSE),
+    ("ed", "e", PAST_TENSE),
+    ("ed", "", PAST_TENSE),
+    ("ying", "ie", PRESENT_PARTICIPLE),
+    ("ing", "e", PRESENT_PARTICIPLE),
+    ("ing", "", PRESENT_PARTICIPLE),
+    ("ier", "y", COMPARATIVE),
+    ("er", "e", COMPARATIVE),
+    ("er", "", COMPARATIVE),
+    ("iest", "y", SUPERLATIVE),
+    ("est", "e", SUPERLATIVE),
+    ("est", "", SUPERLATIVE),
+];
+
+/// Suffixes that double the final consonant of the lemma, as in "stopped" and "biggest".
+const DOUBLING_SUFFIXES: &[(&str, &str)] = &[
+    ("ed", PAST_TENSE),
+    ("ing", PRESENT_PARTICIPLE),
+    ("er", COMPARATIVE),
+    ("est", SUPERLATIVE),
+];
+
+/// The shortest lemma that suffix rules may produce.
+const MIN_LENGTH: usize = 2;
+
+/// Returns the lemmas that an English `word` may be an inflection of, the likeliest first.
+pub fn lemmas(word: &str) -> Vec<Lemma> {
+    let word = word.trim().to_lowercase();
+    let mut lemmas: Vec<Lemma> = Vec::new();
+    let mut add = |lemma: String, relation: &'static str| {
+        if lemma != word && lemma.len() >= MIN_LENGTH && !lemmas.iter().any(|l| l.word == lemma) {
+            lemmas.push(Lemma { word: lemma, relation });
+        }
+    };
+    for (form, lemma, relation) in IRREGULAR {
+        if *form == word {
+            add(lemma.to_string(), relation);
+        }
+    }
+    for (suffix, relation) in DOUBLING_SUFFIXES {
+        if let Some(stem) = word.strip_suffix(suffix) {
+            let mut chars = stem.chars().rev();
+            if let (Some(last), Some(before)) = (chars.next(), chars.next()) {
+                if last == before && !"aeioufls".contains(last) {
+                    add(stem[..stem.len() - last.len_utf8()].to_string(), relation);
+                }
+            }
+        }
+    }
+    for (suffix, replacement, relation) in SUFFIXES {
+        if word.ends_with("ss") && *suffix == "s" {
+            continue;
+        }
+        if let Some(stem) = word.strip_suffix(suffix) {
+            add(format!("{}{}", stem, replacement), relation);
+        }
+    }
+    lemmas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn first(word: &str) -> Option<Lemma> {
+        lemmas(word).into_iter().next()
+    }
+
+    #[test]
+    fn test_irregular_forms() {
+        assert_eq!(Some(Lemma { word: String::from("goose"), relation: PLURAL }), first("geese"));
+        assert_eq!(Some(Lemma { word: String::from("run"), relation: PAST_TENSE }), first("Ran"));
+    }
+
+    #[test]
+    fn test_suffix_rules() {
+        assert_eq!(Some(Lemma { word: String::from("happy"), relation: COMPARATIVE }), first("happier"));
+        assert_eq!(Some(Lemma { word: String::from("city"), relation: PLURAL }), first("cities"));
+        assert!(lemmas("stopped").contains(&Lemma { word: String::from("stop"), relation: PAST_TENSE }));
+        assert!(lemmas("making").contains(&Lemma { word: String::from("make"), relation: PRESENT_PARTICIPLE }));
+        assert!(lemmas("biggest").contains(&Lemma { word: String::from("big"), relation: SUPERLATIVE }));
+        assert!(lemmas("glass").iter().all(|l| l.word != "glas"));
+    }
+
+    #[test]
+    fn test_silent_e_is_restored() {
+        assert_eq!(Some(Lemma { word: String::from("hope"), relation: PAST_TENSE }), first("hoped"));
+        assert_eq!(Some(Lemma { word: String::from("hope"), relation: PRESENT_PARTICIPLE }), first("hoping"));
+        assert_eq!(Some(Lemma { word: String::from("use"), relation: PAST_TENSE }), first("used"));
+        assert_eq!(Some(Lemma { word: String::from("care"), relation: PRESENT_PARTICIPLE }), first("caring"));
+        // The bare stem is still tried when the word never had an "e".
+        assert!(lemmas("walked").contains(&Lemma { word: String::from("walk"), relation: PAST_TENSE }));
+    }
+}
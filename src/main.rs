@@ -1,29 +1,116 @@
 mod api_key;
 mod banner;
+mod batch;
+mod cache;
+mod cli;
 mod client;
 mod components;
+mod config;
+mod http;
+mod lemma;
 mod models;
+mod print;
+mod providers;
+mod spelling;
 mod tui;
 mod ui;
 
-use anyhow::Result;
-use client::parse_response;
+use std::{ fs::File, io::{ self, BufReader }, time::Duration };
+
+use anyhow::{ Context, Result };
+use cli::{ Command, Opt, RelatedOpt };
+use client::{ parse_response, Lookups, WordInfo };
+use config::Config;
+use http::Http;
 use crossterm::event::{ self, Event, KeyCode };
-use models::{ app::{ App, InputMode }, list };
+use models::{ app::{ App, Focus, InputMode, Lookup }, list };
+use print::{ Filter, Output, Printer, Relation };
+use providers::Provider;
 use ratatui::{ backend::CrosstermBackend, Terminal };
 use tui::Tui;
 use tui_input::backend::crossterm::EventHandler;
 
+/// How long to wait for a key press before checking on a running lookup.
+const TICK_RATE: Duration = Duration::from_millis(100);
+
 fn main() -> Result<()> {
-    let mut app = App::new();
+    let opt = Opt::from_command_line();
+    let config = Config::load()?;
+    if let Some(command) = opt.command {
+        return run_command(command, opt.language, &config);
+    }
+
+    let http = Http::new(&config.http)?;
+    let mut app = App::with_provider(Provider::from_config(&config, &http)?);
+    app.lookup_options = config.lookup_options(opt.language);
+    app.languages = app.provider.languages();
+    app.api_keys = app.provider.api_keys();
+    app.cache = config.cache.cache();
+    let mut lookups = Lookups::new()?;
+    match opt.word {
+        Some(word) => {
+            lookups.start(
+                app.provider.clone(),
+                app.cache.clone(),
+                app.lookup_options.clone(),
+                word.clone(),
+                app.is_spelling_fix_enabled
+            );
+            app.input = word.into();
+            app.lookup = Some(Lookup::Search);
+            app.focus = Some(Focus {
+                part_of_speech: opt.pos,
+                input_mode: opt.mode.unwrap_or(InputMode::SelectPartOfSpeech),
+            });
+        }
+        None => {
+            app.input_mode = opt.mode.unwrap_or_default();
+        }
+    }
     let backend = CrosstermBackend::new(std::io::stderr());
     let terminal = Terminal::new(backend)?;
     let mut tui = Tui::new(terminal);
     tui.enter()?;
+    // The synonyms that were last prefetched, so that they are only prefetched once.
+    let mut prefetched: Vec<String> = Vec::new();
+    // Databases are only listed once the settings screen is opened, so that a slow server
+    // does not hold up the interface.
+    let mut databases_listed = false;
+    // Whether the settings screen was shown last time round, to read the API keys as it opens.
+    let mut in_settings = false;
 
     // Start the main loop.
     while !app.should_quit {
+        if let Some(results) = lookups.finished() {
+            app.show_results(results);
+        }
+        if matches!(app.input_mode, InputMode::Settings) && !databases_listed {
+            lookups.list_databases(app.provider.clone());
+            databases_listed = true;
+        }
+        if matches!(app.input_mode, InputMode::Settings) && !in_settings {
+            app.read_key_status();
+        }
+        in_settings = matches!(app.input_mode, InputMode::Settings | InputMode::EnteringKey);
+        if let Some(databases) = lookups.listed_databases() {
+            app.databases = databases;
+        }
+        if let Some(cache) = app.cache.as_ref().filter(|_| app.synonym_list.items != prefetched) {
+            prefetched = app.synonym_list.items.clone();
+            lookups.prefetch(
+                app.provider.clone(),
+                cache.clone(),
+                app.lookup_options.clone(),
+                prefetched.clone(),
+                config.cache.prefetch_concurrency
+            );
+        }
+        app.retry = lookups.status();
         tui.draw(&mut app)?;
+        if !event::poll(TICK_RATE)? {
+            app.spinner = app.spinner.wrapping_add(1);
+            continue;
+        }
         if let Event::Key(key) = event::read()? {
             match app.input_mode {
                 InputMode::Normal =>
@@ -39,6 +126,12 @@ fn main() -> Result<()> {
                         => {
                             app.input_mode = InputMode::SelectDefinition;
                         }
+                        KeyCode::Char(']') => {
+                            app.select_entry(true);
+                        }
+                        KeyCode::Char('[') => {
+                            app.select_entry(false);
+                        }
                         KeyCode::Char('/') => {
                             app.input_mode = InputMode::Editing;
                             app.input.reset();
@@ -46,23 +139,25 @@ fn main() -> Result<()> {
                         KeyCode::Char(':') => {
                             app.input_mode = InputMode::Settings;
                         }
+                        KeyCode::Esc if app.lookup.is_some() => {
+                            lookups.cancel();
+                            app.lookup = None;
+                            app.focus = None;
+                        }
                         _ => {}
                     }
                 InputMode::Editing =>
                     match key.code {
                         KeyCode::Enter => {
                             app.input_mode = InputMode::Normal;
-                            let results = parse_response(
+                            lookups.start(
+                                app.provider.clone(),
+                                app.cache.clone(),
+                                app.lookup_options.clone(),
                                 app.input.to_string(),
                                 app.is_spelling_fix_enabled
                             );
-                            app.results = results.t;
-                            app.suggested_spelling = app.results[0].clone().word.unwrap();
-                            if results.is_spelling_suggested {
-                                app.input_mode = InputMode::Suggesting;
-                            }
-
-                            App::update_stateful_lists(&mut app, list::StatefulListType::All);
+                            app.lookup = Some(Lookup::Search);
                         }
                         KeyCode::Esc => {
                             app.input_mode = InputMode::Normal;
@@ -82,6 +177,12 @@ fn main() -> Result<()> {
                         KeyCode::Char('q') => {
                             app.input_mode = InputMode::Normal;
                         }
+                        KeyCode::Char(']') => {
+                            app.select_entry(true);
+                        }
+                        KeyCode::Char('[') => {
+                            app.select_entry(false);
+                        }
                         KeyCode::Enter => {
                             app.input_mode = InputMode::SelectDefinition;
                             App::update_stateful_lists(
@@ -117,15 +218,20 @@ fn main() -> Result<()> {
                     match key.code {
                         KeyCode::Char('y') | KeyCode::Enter => {
                             app.input_mode = InputMode::Normal;
-                            let results = parse_response(
+                            lookups.start(
+                                app.provider.clone(),
+                                app.cache.clone(),
+                                app.lookup_options.clone(),
                                 app.suggested_spelling.clone(),
                                 app.is_spelling_fix_enabled
                             );
-                            // Prevents Serp API from suggesting the same word repeatedly.
-                            if !results.is_spelling_suggested {
-                                app.results = results.t;
-                                App::update_stateful_lists(&mut app, list::StatefulListType::All);
-                            }
+                            app.lookup = Some(Lookup::Suggestion);
+                        }
+                        KeyCode::Char('j') => {
+                            app.select_suggestion(true);
+                        }
+                        KeyCode::Char('k') => {
+                            app.select_suggestion(false);
                         }
                         KeyCode::Char('n') | KeyCode::Char('q') => {
                             app.input_mode = InputMode::Normal;
@@ -137,11 +243,33 @@ fn main() -> Result<()> {
                         KeyCode::Char('q') => {
                             app.input_mode = InputMode::Editing;
                         }
-                        KeyCode::Char('h') | KeyCode::Char('l') => {
-                            app.is_spelling_fix_enabled = !app.is_spelling_fix_enabled;
+                        KeyCode::Char('j') => {
+                            app.select_setting(true);
+                        }
+                        KeyCode::Char('k') => {
+                            app.select_setting(false);
+                        }
+                        KeyCode::Char('l') => {
+                            app.change_setting(true);
+                        }
+                        KeyCode::Char('h') => {
+                            app.change_setting(false);
                         }
                         _ => {}
                     }
+                InputMode::EnteringKey =>
+                    match key.code {
+                        KeyCode::Enter => {
+                            app.save_api_key();
+                        }
+                        KeyCode::Esc => {
+                            app.key_input.reset();
+                            app.input_mode = InputMode::Settings;
+                        }
+                        _ => {
+                            app.key_input.handle_event(&Event::Key(key));
+                        }
+                    }
             }
         }
     }
@@ -150,3 +278,96 @@ fn main() -> Result<()> {
     tui.exit()?;
     Ok(())
 }
+
+/// Runs a subcommand without entering the user interface.
+fn run_command(command: Command, language: Option<String>, config: &Config) -> Result<()> {
+    match command {
+        Command::ImportWiktionary { input, store } => {
+            let store = store.unwrap_or(config.wiktionary.store.clone());
+            let file = File::open(&input).with_context(||
+                format!("failed to open {}", input.display())
+            )?;
+            let summary = providers::wiktionary::import(file, &store)?;
+            println!(
+                "Imported {} entries for {} words into {} ({} lines skipped).",
+                summary.entries,
+                summary.words,
+                store.display(),
+                summary.skipped
+            );
+        }
+        Command::Define { word, pos, limit, no_color, json } => {
+            let results = look_up_word(&word, language, config)?;
+            let results = Filter { part_of_speech: pos, limit }.apply(&word, results);
+            if json {
+                print::emit(&format!("{}\n", serde_json::to_string_pretty(&Output::new(&word, &results))?))?;
+            } else {
+                if let Some(error) = &results.error {
+                    eprintln!("{}", Printer::stderr(no_color).error(error));
+                } else {
+                    let printer = Printer::new(no_color);
+                    if let Some(notice) = &results.notice {
+                        eprintln!("({})", notice);
+                    }
+                    print::emit(&printer.entries(&results.t))?;
+                }
+            }
+            if results.error.is_some() {
+                std::process::exit(1);
+            }
+        }
+        Command::Batch { input, parallel, pos, limit } => {
+            let words = match &input {
+                Some(path) => {
+                    let file = File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+                    batch::read_words(BufReader::new(file))?
+                }
+                None => batch::read_words(io::stdin().lock())?,
+            };
+            let http = Http::new(&config.http)?;
+            let provider = Provider::from_config(config, &http)?;
+            let options = config.lookup_options(language);
+            let filter = Filter { part_of_speech: pos, limit };
+            let runtime = tokio::runtime::Runtime::new()?;
+            runtime.block_on(
+                batch::look_up(provider, config.cache.cache(), options, words, filter, parallel, &mut io::stdout())
+            )?;
+        }
+        Command::Syn(opt) => {
+            print_related(opt, Relation::Synonyms, language, config)?;
+        }
+        Command::Ant(opt) => {
+            print_related(opt, Relation::Antonyms, language, config)?;
+        }
+    }
+    Ok(())
+}
+
+/// Looks `word` up for a subcommand, waiting until it is done.
+fn look_up_word(word: &str, language: Option<String>, config: &Config) -> Result<WordInfo> {
+    let http = Http::new(&config.http)?;
+    let provider = Provider::from_config(config, &http)?;
+    let options = config.lookup_options(language);
+    let runtime = tokio::runtime::Runtime::new()?;
+    Ok(
+        runtime.block_on(
+            parse_response(&*provider, config.cache.cache().as_ref(), &options, word.to_string(), false)
+        )
+    )
+}
+
+/// Prints the synonyms or antonyms of a word, one per line, for `syn` and `ant`.
+fn print_related(opt: RelatedOpt, relation: Relation, language: Option<String>, config: &Config) -> Result<()> {
+    let results = look_up_word(&opt.word, language, config)?;
+    let results = Filter { part_of_speech: opt.pos, limit: None }.apply(&opt.word, results);
+    if let Some(error) = &results.error {
+        eprintln!("{}", Printer::stderr(false).error(error));
+        std::process::exit(1);
+    }
+    let words = print::related(&results.t, relation, opt.definition, opt.dedupe);
+    if words.is_empty() {
+        std::process::exit(1);
+    }
+    print::emit(&words.iter().map(|word| format!("{}\n", word)).collect::<String>())?;
+    Ok(())
+}
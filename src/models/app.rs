@@ -1,26 +1,83 @@
+use std::str::FromStr;
+
 use tui_input::Input;
 
-use crate::models::{
-    data::Thesaurus,
-    list::{StatefulList, StatefulListType},
+use crate::{
+    api_key,
+    cache::{ Cache, Origin },
+    client::WordInfo,
+    models::{
+        data::Thesaurus,
+        errors::ErrorReport,
+        list::{StatefulList, StatefulListType},
+    },
+    providers::{ dictd::ALL_DATABASES, Database, LookupOptions, Provider },
 };
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub enum InputMode {
+    #[default]
     Normal,
     Editing,
     SelectPartOfSpeech,
     SelectDefinition,
     Suggesting,
     Settings,
+    /// Typing the API key selected in the settings screen.
+    EnteringKey,
 }
 
-impl Default for InputMode {
-    fn default() -> Self {
-        InputMode::Normal
+impl FromStr for InputMode {
+    type Err = String;
+
+    /// Parses the modes that the interface can be started in, such as `select-definition`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "normal" => Ok(InputMode::Normal),
+            "editing" => Ok(InputMode::Editing),
+            "select-part-of-speech" => Ok(InputMode::SelectPartOfSpeech),
+            "select-definition" => Ok(InputMode::SelectDefinition),
+            "settings" => Ok(InputMode::Settings),
+            _ => {
+                Err(
+                    format!(
+                        "unknown mode {:?}; expected normal, editing, select-part-of-speech, select-definition or settings",
+                        s
+                    )
+                )
+            }
+        }
     }
 }
 
+/// Where the interface goes once the word it was opened with has been looked up.
+#[derive(Clone, Debug, Default)]
+pub struct Focus {
+    /// The part of speech to select, such as `verb`.
+    pub part_of_speech: Option<String>,
+    pub input_mode: InputMode,
+}
+
+/// What a running lookup was started for.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Lookup {
+    /// A word entered in the search bar.
+    Search,
+    /// A suggested spelling that was accepted.
+    Suggestion,
+}
+
+/// An entry of the settings screen.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Setting {
+    #[default]
+    SpellingSuggestion,
+    Language,
+    Database,
+    /// The key of the service at this index of `App::api_keys`.
+    ApiKey(usize),
+}
+
 /// Application.
 #[derive(Clone, Debug, Default)]
 pub struct App {
@@ -28,12 +85,45 @@ pub struct App {
     pub input: Input,
     pub input_mode: InputMode,
     pub results: Vec<Thesaurus>,
-    pub has_results: bool,
+    /// Index of the entry in `results` that is being shown.
+    pub entry_index: usize,
     pub part_of_speech_list: StatefulList<String>,
     pub definition_list: StatefulList<String>,
     pub is_spelling_fix_enabled: bool,
     pub suggested_spelling: String,
+    /// Every spelling offered for the last word, the most likely first.
+    pub suggestions: Vec<String>,
     pub synonym_list: StatefulList<String>,
+    /// Antonyms of the selected definition, followed by those of its meaning.
+    pub antonyms: Vec<String>,
+    pub provider: Provider,
+    pub lookup_options: LookupOptions,
+    pub languages: Vec<String>,
+    pub databases: Vec<Database>,
+    pub setting: Setting,
+    /// Services whose API keys the provider uses.
+    pub api_keys: Vec<String>,
+    /// Whether each of `api_keys` is set, read when the settings screen opens rather than on
+    /// every redraw.
+    pub key_status: Vec<String>,
+    /// The API key being typed, kept apart from the search input.
+    pub key_input: Input,
+    /// Why the last lookup failed, shown in place of the results.
+    pub error: Option<ErrorReport>,
+    /// Says when the results are for another form of the word, such as its singular.
+    pub notice: Option<String>,
+    /// Lookup results kept on disk, unless the cache is disabled.
+    pub cache: Option<Cache>,
+    /// Where the results being shown came from.
+    pub origin: Option<Origin>,
+    /// The lookup that is running, if any.
+    pub lookup: Option<Lookup>,
+    /// Advances while a lookup runs, to animate the spinner.
+    pub spinner: usize,
+    /// Why the running lookup is waiting, such as being rate limited.
+    pub retry: Option<String>,
+    /// Where to go once the running lookup has finished, when the interface was opened on a word.
+    pub focus: Option<Focus>,
 }
 
 impl App {
@@ -41,12 +131,23 @@ impl App {
         Self::default()
     }
 
+    pub fn with_provider(provider: Provider) -> Self {
+        Self {
+            provider,
+            ..Self::new()
+        }
+    }
+
     pub fn quit(&mut self) {
         self.should_quit = true;
     }
 
     pub fn update_instructions(&mut self) -> String {
         match self.input_mode {
+            InputMode::Normal if self.lookup.is_some() => String::from("<ESC>: Cancel"),
+            InputMode::Normal if self.results.len() > 1 => {
+                String::from("[, ]: Change entry  j, k: Change part of speech  /: Insert")
+            }
             InputMode::Normal if self.part_of_speech_list.items.len() == 1 => {
                 String::from("l, h: Change definition  /: Insert")
             }
@@ -56,12 +157,50 @@ impl App {
             InputMode::Editing => String::from("<ENTER>: Search  <ESC>: Exit"),
             InputMode::SelectPartOfSpeech => String::from("<ENTER>: Select"),
             InputMode::SelectDefinition => String::from("l, h: Change definition  /: Insert"),
-            InputMode::Settings => self.toggle_spelling_suggestion(),
+            InputMode::Settings => self.describe_setting(),
+            InputMode::EnteringKey => String::from("<ENTER>: Save  <ESC>: Cancel"),
+            InputMode::Suggesting if self.suggestions.len() > 1 => {
+                String::from("j, k: Other spellings  <ENTER>: Continue")
+            }
             InputMode::Suggesting => String::from("<ENTER>: Continue"),
             _ => String::from("/: Insert"),
         }
     }
 
+    /// Shows the results of the lookup that has finished.
+    pub fn show_results(&mut self, results: WordInfo) {
+        match self.lookup.take() {
+            Some(Lookup::Search) => {
+                self.results = results.t;
+                self.entry_index = 0;
+                self.error = results.error;
+                self.notice = results.notice;
+                self.origin = results.origin;
+                self.suggestions = results.suggestions;
+                self.suggested_spelling = self.suggestions.first().cloned().unwrap_or_default();
+                if results.is_spelling_suggested {
+                    self.input_mode = InputMode::Suggesting;
+                }
+                self.update_stateful_lists(StatefulListType::All);
+                if let Some(focus) = self.focus.take() {
+                    if !results.is_spelling_suggested && self.error.is_none() {
+                        self.focus_on(focus);
+                    }
+                }
+            }
+            // Prevents Serp API from suggesting the same word repeatedly.
+            Some(Lookup::Suggestion) if !results.is_spelling_suggested => {
+                self.results = results.t;
+                self.entry_index = 0;
+                self.error = results.error;
+                self.notice = results.notice;
+                self.origin = results.origin;
+                self.update_stateful_lists(StatefulListType::All);
+            }
+            _ => {}
+        }
+    }
+
     pub fn update_stateful_lists(&mut self, list_type: StatefulListType) {
         match list_type {
             StatefulListType::PartOfSpeech => {
@@ -81,65 +220,256 @@ impl App {
         }
     }
 
-    fn toggle_spelling_suggestion(&mut self) -> String {
-        format!("Spelling suggestion: {}", self.is_spelling_fix_enabled)
+    /// Returns the settings that apply to the current provider.
+    fn settings(&self) -> Vec<Setting> {
+        let mut settings = vec![Setting::SpellingSuggestion];
+        if self.languages.len() > 1 {
+            settings.push(Setting::Language);
+        }
+        if !self.databases.is_empty() {
+            settings.push(Setting::Database);
+        }
+        settings.extend((0..self.api_keys.len()).map(Setting::ApiKey));
+        settings
     }
 
-    fn update_part_of_speech_list(&mut self) {
-        if !self.results.is_empty() {
-            let meanings = self.results[0].meanings.clone();
-            if meanings.is_some() {
-                let part_of_speech_list: Vec<String> = meanings
-                    .unwrap()
+    /// Moves to the next (or previous) entry of the settings screen.
+    pub fn select_setting(&mut self, forward: bool) {
+        let settings = self.settings();
+        let idx = settings.iter().position(|s| *s == self.setting).unwrap_or(0);
+        let idx = if forward {
+            (idx + 1) % settings.len()
+        } else {
+            (idx + settings.len() - 1) % settings.len()
+        };
+        self.setting = settings[idx];
+    }
+
+    /// Changes the value of the selected setting.
+    pub fn change_setting(&mut self, forward: bool) {
+        match self.setting {
+            Setting::SpellingSuggestion => {
+                self.is_spelling_fix_enabled = !self.is_spelling_fix_enabled;
+            }
+            Setting::Language => {
+                let idx = self.languages
                     .iter()
-                    .map(|i| i.partOfSpeech.clone().unwrap_or(String::from("")))
-                    .collect();
-                self.part_of_speech_list =
-                    StatefulList::with_items(part_of_speech_list, StatefulListType::PartOfSpeech);
+                    .position(|l| *l == self.lookup_options.language)
+                    .unwrap_or(0);
+                let idx = if forward {
+                    (idx + 1) % self.languages.len()
+                } else {
+                    (idx + self.languages.len() - 1) % self.languages.len()
+                };
+                self.lookup_options.language = self.languages[idx].clone();
+            }
+            Setting::Database => {
+                let mut choices = vec![ALL_DATABASES.to_string()];
+                choices.extend(self.databases.iter().map(|d| d.name.clone()));
+                let current = self.lookup_options.database.as_deref().unwrap_or(ALL_DATABASES);
+                let idx = choices.iter().position(|c| c == current).unwrap_or(0);
+                let idx = if forward {
+                    (idx + 1) % choices.len()
+                } else {
+                    (idx + choices.len() - 1) % choices.len()
+                };
+                self.lookup_options.database = Some(choices[idx].clone());
+            }
+            Setting::ApiKey(_) => {
+                self.input_mode = InputMode::EnteringKey;
+                self.key_input.reset();
+            }
+        }
+    }
 
-                // Select the first item as default.
-                self.part_of_speech_list.state.select(Some(0))
+    /// Reads whether the key of each service is set, to show on the settings screen.
+    pub fn read_key_status(&mut self) {
+        self.key_status = self.api_keys
+            .iter()
+            .map(|service| {
+                match api_key::get(service) {
+                    Ok(Some(_)) => String::from("set"),
+                    Ok(None) => String::from("not set"),
+                    Err(err) => err.to_string(),
+                }
+            })
+            .collect();
+    }
+
+    /// Saves the key typed for the selected service and returns to the settings screen.
+    /// An empty key removes the saved one.
+    pub fn save_api_key(&mut self) {
+        if let Setting::ApiKey(idx) = self.setting {
+            match api_key::set(&self.api_keys[idx], self.key_input.value().trim()) {
+                Ok(_) => {
+                    self.error = None;
+                }
+                Err(err) => {
+                    self.error = Some(err.report());
+                }
             }
         }
+        self.read_key_status();
+        self.key_input.reset();
+        self.input_mode = InputMode::Settings;
     }
 
-    fn update_definition_list(&mut self) {
-        if !self.results.is_empty() {
-            if let Some(idx) = self.part_of_speech_list.state.selected() {
-                let definitions = Thesaurus::unwrap_meanings_at(idx, &self.results[0]).1;
-                let definitions: Vec<String> = definitions
+    fn describe_setting(&mut self) -> String {
+        let description = match self.setting {
+            Setting::SpellingSuggestion => {
+                format!("Spelling suggestion: {}", self.is_spelling_fix_enabled)
+            }
+            Setting::Language => format!("Language: {}", self.lookup_options.language),
+            Setting::Database => {
+                let name = self.lookup_options.database.as_deref().unwrap_or(ALL_DATABASES);
+                let description = self.databases
                     .iter()
-                    .map(|i| i.definition.clone().unwrap_or(String::from("")))
-                    .collect();
-                self.definition_list =
-                    StatefulList::with_items(definitions, StatefulListType::Definition);
+                    .find(|d| d.name == name)
+                    .map_or("All databases", |d| d.description.as_str());
+                format!("Database: {} ({})", name, description)
+            }
+            Setting::ApiKey(idx) => {
+                let status = self.key_status.get(idx).map_or("unknown", String::as_str);
+                format!("{} key: {}  l: Enter a new key", self.api_keys[idx], status)
+            }
+        };
+        if self.settings().len() > 1 {
+            format!("{}  j, k: Next setting", description)
+        } else {
+            description
+        }
+    }
+
+    /// Returns the entry being shown. Homographs, such as "bass" the fish and "bass" the sound,
+    /// come back as separate entries.
+    pub fn entry(&self) -> Option<&Thesaurus> {
+        self.results.get(self.entry_index)
+    }
+
+    /// Shows the next or previous entry, wrapping around at either end.
+    pub fn select_entry(&mut self, forward: bool) {
+        if self.results.len() < 2 {
+            return;
+        }
+        let count = self.results.len();
+        self.entry_index = if forward {
+            (self.entry_index + 1) % count
+        } else {
+            (self.entry_index + count - 1) % count
+        };
+        self.update_stateful_lists(StatefulListType::All);
+    }
 
-                // Select the first item as default.
-                self.definition_list.state.select(Some(0))
+    /// Selects the first entry with the part of speech in `focus`, then switches to its mode.
+    fn focus_on(&mut self, focus: Focus) {
+        if let Some(wanted) = &focus.part_of_speech {
+            let has_part_of_speech = |entry: &Thesaurus| {
+                entry.meanings
+                    .iter()
+                    .flatten()
+                    .position(|m| m.partOfSpeech.as_deref().is_some_and(|pos| pos.eq_ignore_ascii_case(wanted.trim())))
+            };
+            let found = self.results
+                .iter()
+                .enumerate()
+                .find_map(|(i, entry)| Some((i, has_part_of_speech(entry)?)));
+            if let Some((entry_index, pos_idx)) = found {
+                self.entry_index = entry_index;
+                self.update_stateful_lists(StatefulListType::All);
+                self.part_of_speech_list.state.select(Some(pos_idx));
+                self.update_stateful_lists(StatefulListType::Definition);
+                self.update_stateful_lists(StatefulListType::Synonym);
             }
         }
+        self.input_mode = focus.input_mode;
     }
 
-    fn update_synonym_list(&mut self) {
-        if !self.results.is_empty() {
-            let pos_idx = self.part_of_speech_list.state.selected().unwrap_or(0);
-            let definitions = Thesaurus::unwrap_meanings_at(pos_idx, &self.results[0]).1;
-            let def_idx = self.definition_list.state.selected().unwrap_or(0);
-            let definition = &definitions[def_idx];
-            let synonyms = definition.clone().synonyms;
-            if synonyms.is_some() {
-                let synonyms: Vec<String> = synonyms.unwrap().iter().map(|i| i.clone()).collect();
-                self.synonym_list = StatefulList::with_items(synonyms, StatefulListType::Synonym);
-            } else {
-                self.synonym_list = StatefulList::with_items(Vec::new(), StatefulListType::Synonym);
+    /// Offers the next or previous spelling, wrapping around at either end.
+    pub fn select_suggestion(&mut self, forward: bool) {
+        let count = self.suggestions.len();
+        if count < 2 {
+            return;
+        }
+        let current = self.suggestions
+            .iter()
+            .position(|s| *s == self.suggested_spelling)
+            .unwrap_or(0);
+        let next = if forward { (current + 1) % count } else { (current + count - 1) % count };
+        self.suggested_spelling = self.suggestions[next].clone();
+    }
+
+    fn update_part_of_speech_list(&mut self) {
+        let meanings = match self.entry().and_then(|entry| entry.meanings.clone()) {
+            Some(meanings) => meanings,
+            None => {
+                // Nothing is left of the last word to select after a failed lookup.
+                self.part_of_speech_list = StatefulList::with_items(Vec::new(), StatefulListType::PartOfSpeech);
+                return;
+            }
+        };
+        let part_of_speech_list: Vec<String> = meanings
+            .iter()
+            .map(|i| i.partOfSpeech.clone().unwrap_or(String::from("")))
+            .collect();
+        self.part_of_speech_list =
+            StatefulList::with_items(part_of_speech_list, StatefulListType::PartOfSpeech);
+
+        // Select the first item as default.
+        self.part_of_speech_list.state.select(Some(0))
+    }
+
+    fn update_definition_list(&mut self) {
+        let idx = self.part_of_speech_list.state.selected();
+        let definitions = match (self.entry(), idx) {
+            (Some(entry), Some(idx)) => Thesaurus::unwrap_meanings_at(idx, entry).1,
+            _ => {
+                self.definition_list = StatefulList::with_items(Vec::new(), StatefulListType::Definition);
+                return;
             }
+        };
+        let definitions: Vec<String> = definitions
+            .iter()
+            .map(|i| i.definition.clone().unwrap_or(String::from("")))
+            .collect();
+        self.definition_list =
+            StatefulList::with_items(definitions, StatefulListType::Definition);
+
+        // Select the first item as default.
+        self.definition_list.state.select(Some(0))
+    }
+
+    fn update_synonym_list(&mut self) {
+        let pos_idx = self.part_of_speech_list.state.selected().unwrap_or(0);
+        let def_idx = self.definition_list.state.selected().unwrap_or(0);
+        let words = self.entry().and_then(|entry| {
+            let definitions = Thesaurus::unwrap_meanings_at(pos_idx, entry).1;
+            let definition = definitions.get(def_idx)?;
+            // The Free Dictionary API lists most synonyms and antonyms on the meaning rather than the definition.
+            let meaning = entry.meanings.as_ref().and_then(|m| m.get(pos_idx));
+            let synonyms = combine(&definition.synonyms, meaning.and_then(|m| m.synonyms.as_ref()));
+            let antonyms = combine(&definition.antonyms, meaning.and_then(|m| m.antonyms.as_ref()));
+            Some((synonyms, antonyms))
+        });
+        let (synonyms, antonyms) = words.unwrap_or_default();
+        self.synonym_list = StatefulList::with_items(synonyms, StatefulListType::Synonym);
+        self.antonyms = antonyms;
+    }
+}
+
+/// Joins two lists of words, leaving out repeats.
+fn combine(first: &Option<Vec<String>>, second: Option<&Vec<String>>) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    for word in first.iter().flatten().chain(second.into_iter().flatten()) {
+        if !words.iter().any(|w| w.eq_ignore_ascii_case(word)) {
+            words.push(word.clone());
         }
     }
+    words
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::models::data::{Definition, Meaning};
+    use crate::models::{ data::{Definition, Meaning}, errors::ApiError };
 
     use super::*;
     use pretty_assertions::assert_eq;
@@ -147,7 +477,7 @@ mod tests {
     fn mock_app_in(input_mode: InputMode) -> App {
         let mut mock_app = App::new();
         mock_app.input_mode = input_mode;
-        return mock_app;
+        mock_app
     }
 
     fn mock_part_of_speech() -> String {
@@ -158,6 +488,7 @@ mod tests {
         Meaning {
             partOfSpeech: p,
             definitions: d,
+            ..Meaning::default()
         }
     }
 
@@ -167,14 +498,15 @@ mod tests {
             example: None,
             synonyms: None,
             antonyms: None,
+            source: None,
         }
     }
 
     fn mock_results_with(m: Vec<Meaning>) -> Vec<Thesaurus> {
         vec![Thesaurus {
             word: Some(String::from("mock")),
-            origin: None,
             meanings: Some(m),
+            ..Thesaurus::default()
         }]
     }
 
@@ -218,6 +550,125 @@ mod tests {
         assert_eq!(Some(0), mock_app.definition_list.state.selected());
     }
 
+    #[test]
+    fn test_update_synonym_list_with_meaning_level_words() {
+        let mut mock_app = mock_app_in(InputMode::default());
+        let mut mock_definition = mock_definition_with(Some(String::from("Definition 1")));
+        mock_definition.synonyms = Some(vec![String::from("glad")]);
+        let mut mock_meaning = mock_meaning_with(Some(mock_part_of_speech()), Some(vec![mock_definition]));
+        mock_meaning.synonyms = Some(vec![String::from("Glad"), String::from("content")]);
+        mock_meaning.antonyms = Some(vec![String::from("sad")]);
+        mock_app.results = mock_results_with(vec![mock_meaning]);
+        App::update_stateful_lists(&mut mock_app, StatefulListType::All);
+        assert_eq!(vec![String::from("glad"), String::from("content")], mock_app.synonym_list.items);
+        assert_eq!(vec![String::from("sad")], mock_app.antonyms);
+    }
+
+    #[test]
+    fn test_select_entry() {
+        let mut mock_app = mock_app_in(InputMode::Normal);
+        let mock_definitions = Some(vec![mock_definition_with(Some(String::from("Definition 1")))]);
+        mock_app.results = [
+            mock_results_with(vec![mock_meaning_with(Some(String::from("noun")), mock_definitions.clone())]),
+            mock_results_with(vec![mock_meaning_with(Some(String::from("adjective")), mock_definitions)]),
+        ].concat();
+        App::update_stateful_lists(&mut mock_app, StatefulListType::All);
+        assert_eq!(vec![String::from("noun")], mock_app.part_of_speech_list.items);
+        mock_app.select_entry(true);
+        assert_eq!(1, mock_app.entry_index);
+        assert_eq!(vec![String::from("adjective")], mock_app.part_of_speech_list.items);
+        mock_app.select_entry(true);
+        assert_eq!(0, mock_app.entry_index);
+        mock_app.select_entry(false);
+        assert_eq!(1, mock_app.entry_index);
+        assert_eq!(
+            App::update_instructions(&mut mock_app),
+            "[, ]: Change entry  j, k: Change part of speech  /: Insert"
+        );
+    }
+
+    #[test]
+    fn test_select_suggestion() {
+        let mut mock_app = mock_app_in(InputMode::Suggesting);
+        mock_app.suggestions = vec![String::from("happy"), String::from("harpy")];
+        mock_app.suggested_spelling = String::from("happy");
+        mock_app.select_suggestion(true);
+        assert_eq!("harpy", mock_app.suggested_spelling);
+        mock_app.select_suggestion(true);
+        assert_eq!("happy", mock_app.suggested_spelling);
+        mock_app.select_suggestion(false);
+        assert_eq!("harpy", mock_app.suggested_spelling);
+        assert_eq!(
+            App::update_instructions(&mut mock_app),
+            "j, k: Other spellings  <ENTER>: Continue"
+        );
+    }
+
+    #[test]
+    fn test_show_results_of_suggested_spelling() {
+        let mut mock_app = mock_app_in(InputMode::Normal);
+        mock_app.lookup = Some(Lookup::Search);
+        assert_eq!("<ESC>: Cancel", App::update_instructions(&mut mock_app));
+        mock_app.show_results(WordInfo {
+            is_spelling_suggested: true,
+            suggestions: vec![String::from("happy"), String::from("harpy")],
+            ..WordInfo::default()
+        });
+        assert!(matches!(mock_app.input_mode, InputMode::Suggesting));
+        assert_eq!("happy", mock_app.suggested_spelling);
+        assert_eq!(None, mock_app.lookup);
+
+        // Results that arrive after the lookup was cancelled are not shown.
+        mock_app.show_results(WordInfo {
+            error: Some(ApiError::Network(String::from("unreachable")).report()),
+            ..WordInfo::default()
+        });
+        assert_eq!(None, mock_app.error);
+    }
+
+    #[test]
+    fn test_failed_lookup_clears_lists() {
+        let mut mock_app = mock_app_in(InputMode::Normal);
+        let definitions = vec![mock_definition_with(Some(String::from("Definition 1")))];
+        let meanings = vec![mock_meaning_with(Some(mock_part_of_speech()), Some(definitions))];
+        mock_app.lookup = Some(Lookup::Search);
+        mock_app.show_results(WordInfo { t: mock_results_with(meanings), ..WordInfo::default() });
+        assert_eq!(1, mock_app.definition_list.items.len());
+
+        mock_app.lookup = Some(Lookup::Search);
+        mock_app.show_results(WordInfo::failed(ApiError::NotFound.report()));
+        assert!(mock_app.part_of_speech_list.items.is_empty());
+        assert_eq!(None, mock_app.part_of_speech_list.state.selected());
+        assert!(mock_app.definition_list.items.is_empty());
+        assert_eq!(None, mock_app.definition_list.state.selected());
+        assert!(mock_app.synonym_list.items.is_empty());
+        assert!(mock_app.antonyms.is_empty());
+        assert_eq!("/: Insert", App::update_instructions(&mut mock_app));
+    }
+
+    #[test]
+    fn test_show_results_with_focus() {
+        let mut mock_app = mock_app_in(InputMode::Normal);
+        mock_app.lookup = Some(Lookup::Search);
+        mock_app.focus = Some(Focus {
+            part_of_speech: Some(String::from("Verb")),
+            input_mode: "select-part-of-speech".parse().unwrap(),
+        });
+        let meanings = vec![
+            mock_meaning_with(Some(String::from("noun")), None),
+            mock_meaning_with(
+                Some(String::from("verb")),
+                Some(vec![mock_definition_with(Some(String::from("To do it.")))])
+            )
+        ];
+        mock_app.show_results(WordInfo { t: mock_results_with(meanings), ..WordInfo::default() });
+        assert!(matches!(mock_app.input_mode, InputMode::SelectPartOfSpeech));
+        assert_eq!(Some(1), mock_app.part_of_speech_list.state.selected());
+        assert_eq!(vec![String::from("To do it.")], mock_app.definition_list.items);
+        assert!(mock_app.focus.is_none());
+        assert!("suggesting".parse::<InputMode>().is_err());
+    }
+
     #[test]
     fn test_instructions_in_normal_mode() {
         let mut mock_app = mock_app_in(InputMode::Normal);
@@ -292,6 +743,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_change_database_setting() {
+        let mut mock_app = mock_app_in(InputMode::Settings);
+        mock_app.databases = vec![Database {
+            name: String::from("wn"),
+            description: String::from("WordNet (r) 3.0 (2006)"),
+        }];
+        mock_app.select_setting(true);
+        assert_eq!(Setting::Database, mock_app.setting);
+        mock_app.change_setting(true);
+        assert_eq!(Some(String::from("wn")), mock_app.lookup_options.database);
+        assert_eq!(
+            App::update_instructions(&mut mock_app),
+            "Database: wn (WordNet (r) 3.0 (2006))  j, k: Next setting"
+        );
+        mock_app.change_setting(true);
+        assert_eq!(Some(String::from("*")), mock_app.lookup_options.database);
+    }
+
+    #[test]
+    fn test_api_key_setting() {
+        let mut mock_app = mock_app_in(InputMode::Settings);
+        mock_app.api_keys = vec![String::from("serpapi")];
+        mock_app.select_setting(true);
+        assert_eq!(Setting::ApiKey(0), mock_app.setting);
+        mock_app.key_status = vec![String::from("not set")];
+        assert_eq!(
+            App::update_instructions(&mut mock_app),
+            "serpapi key: not set  l: Enter a new key  j, k: Next setting"
+        );
+        mock_app.change_setting(true);
+        assert!(matches!(mock_app.input_mode, InputMode::EnteringKey));
+        assert_eq!(
+            App::update_instructions(&mut mock_app),
+            "<ENTER>: Save  <ESC>: Cancel"
+        );
+    }
+
+    #[test]
+    fn test_read_key_status() {
+        let mut mock_app = mock_app_in(InputMode::Settings);
+        mock_app.api_keys = vec![String::from("thesaurust-test")];
+        std::env::set_var(api_key::env_var("thesaurust-test"), "secret");
+        mock_app.read_key_status();
+        std::env::remove_var(api_key::env_var("thesaurust-test"));
+        assert_eq!(vec![String::from("set")], mock_app.key_status);
+    }
+
+    #[test]
+    fn test_change_language_setting() {
+        let mut mock_app = mock_app_in(InputMode::Settings);
+        mock_app.languages = vec![String::from("en"), String::from("es"), String::from("de")];
+        mock_app.select_setting(true);
+        assert_eq!(Setting::Language, mock_app.setting);
+        mock_app.change_setting(false);
+        assert_eq!("de", mock_app.lookup_options.language);
+        assert_eq!(
+            App::update_instructions(&mut mock_app),
+            "Language: de  j, k: Next setting"
+        );
+    }
+
     #[test]
     fn test_instructions_in_suggesting_mode() {
         let mut mock_app = mock_app_in(InputMode::Suggesting);
@@ -1,98 +1,100 @@
 use std::fmt::Debug;
 
-use serde_derive::Deserialize;
+use serde_derive::{ Deserialize, Serialize };
 
 /// Components of a response from the Free Dictionary API.
-#[derive(Clone, Deserialize, Debug)]
+#[derive(Clone, Default, Deserialize, Serialize, Debug)]
+#[allow(non_snake_case)]
 pub struct Thesaurus {
     pub word: Option<String>,
+    /// The pronunciation in IPA, such as `/həˈləʊ/`.
+    pub phonetic: Option<String>,
+    pub phonetics: Option<Vec<Phonetic>>,
+    #[allow(dead_code)]
     pub origin: Option<String>,
 
     // A word can have multiple meanings, hence it is represented as an array of meanings.
     pub meanings: Option<Vec<Meaning>>,
-}
-
-impl Default for Thesaurus {
-    fn default() -> Self {
-        Thesaurus {
-            word: None,
-            origin: None,
-            meanings: None,
-        }
-    }
+    /// Pages that the entry was taken from.
+    pub sourceUrls: Option<Vec<String>>,
+    /// The license that the entry is published under.
+    pub license: Option<License>,
 }
 
 impl Thesaurus {
     /// A function that unwraps the contents inside `Meaning`. It returns a tuple that contains the `partOfSpeech` and `Vec<Definition>`.
+    /// Both are empty when there is no meaning at `index`, such as after switching to an entry with fewer meanings.
     pub fn unwrap_meanings_at(index: usize, thesaurus: &Thesaurus) -> (String, Vec<Definition>) {
-        //TODO: Create unit test to check index and array length.
-        if let Some(meanings) = thesaurus.meanings.clone() {
-            let meaning = meanings[index].clone();
+        let meaning = thesaurus.meanings.as_ref().and_then(|meanings| meanings.get(index));
+        if let Some(meaning) = meaning {
             if let Some(part_of_speech) = meaning.partOfSpeech.clone() {
-                let definitions = meaning.definitions.clone().unwrap();
+                let definitions = meaning.definitions.clone().unwrap_or_default();
                 return (part_of_speech, definitions);
-            } else {
-                return (String::from(""), Vec::<Definition>::default());
-            };
-        } else {
-            return (String::from(""), Vec::<Definition>::default());
+            }
         }
+        (String::from(""), Vec::<Definition>::default())
     }
+}
 
-    /// A function that prompts the user to re-enter the word because the word cannot be found in the API.
-    pub fn inject_message(msg: String) -> Vec<Thesaurus> {
-        let definition = Definition {
-            definition: None,
-            example: None,
-            synonyms: None,
-            antonyms: None,
-        };
-        let d = vec![definition];
-        let meaning = Meaning {
-            partOfSpeech: Some(String::from("/")),
-            definitions: Some(d),
-        };
-        let m = vec![meaning];
-        let thesaurus = Thesaurus {
-            word: Some(msg),
-            origin: None,
-            meanings: Some(m),
-        };
-        vec![thesaurus]
-    }
+/// A pronunciation, with a recording of it when one exists.
+#[derive(Clone, Default, Deserialize, Serialize, Debug)]
+#[allow(non_snake_case)]
+pub struct Phonetic {
+    pub text: Option<String>,
+    pub audio: Option<String>,
+    pub sourceUrl: Option<String>,
+    pub license: Option<License>,
 }
 
-#[derive(Clone, Deserialize, Debug)]
+#[derive(Clone, Default, Deserialize, Serialize, Debug)]
+pub struct License {
+    pub name: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Clone, Default, Deserialize, Serialize, Debug)]
 #[allow(non_snake_case)]
 pub struct Meaning {
     pub partOfSpeech: Option<String>,
     pub definitions: Option<Vec<Definition>>,
+    /// Synonyms that apply to every definition of the meaning.
+    pub synonyms: Option<Vec<String>>,
+    pub antonyms: Option<Vec<String>>,
 }
 
-impl Default for Meaning {
-    fn default() -> Self {
-        Meaning {
-            partOfSpeech: None,
-            definitions: None,
-        }
-    }
-}
-
-#[derive(Clone, Deserialize, Debug)]
+#[derive(Clone, Default, Deserialize, Serialize, Debug)]
 pub struct Definition {
     pub definition: Option<String>,
     pub example: Option<String>,
     pub synonyms: Option<Vec<String>>,
     pub antonyms: Option<Vec<String>>,
+    /// The provider that the definition came from, when several are combined.
+    pub source: Option<String>,
 }
 
-impl Default for Definition {
-    fn default() -> Self {
-        Definition {
-            definition: None,
-            example: None,
-            synonyms: None,
-            antonyms: None,
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_unwrap_meanings_at() {
+        let thesaurus = Thesaurus {
+            meanings: Some(
+                vec![Meaning {
+                    partOfSpeech: Some(String::from("noun")),
+                    definitions: Some(vec![Definition::default()]),
+                    ..Meaning::default()
+                }]
+            ),
+            ..Thesaurus::default()
+        };
+        let (part_of_speech, definitions) = Thesaurus::unwrap_meanings_at(0, &thesaurus);
+        assert_eq!("noun", part_of_speech);
+        assert_eq!(1, definitions.len());
+        let (part_of_speech, definitions) = Thesaurus::unwrap_meanings_at(1, &thesaurus);
+        assert_eq!("", part_of_speech);
+        assert!(definitions.is_empty());
+        assert!(Thesaurus::unwrap_meanings_at(0, &Thesaurus::default()).1.is_empty());
     }
 }
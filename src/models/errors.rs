@@ -1,11 +1,165 @@
+use std::{ fmt, time::Duration };
+
+use crate::api_key;
+
 #[derive(Debug)]
 pub enum ApiError {
+    /// The word has no entry.
+    NotFound,
+    /// The server could not be connected to.
+    Network(String),
+    /// The server took too long to respond.
+    Timeout(String),
+    /// A response did not have the shape that was expected of it.
+    Parse(String),
+    /// The service refused requests because too many were made or the plan's quota is used up.
+    QuotaExceeded(String),
+    /// The service asked for a longer wait before the next request than is worth retrying after.
+    RateLimited(String, Duration),
+    /// The service with this name needs an API key, but none is set.
+    MissingKey(String),
     HttpError(reqwest::Error),
-    InvalidInput,
+    IoError(std::io::Error),
+    ProtocolError(String),
+    SpellingError(String),
+    /// An API key could not be read or saved.
+    KeyError(String),
+    /// A setting in the config file cannot be used.
+    ConfigError(String),
+}
+
+/// An error as it is shown to the user.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ErrorReport {
+    /// What kind of error it is, such as `Not found`.
+    pub title: String,
+    pub message: String,
+    /// What the user can do about it.
+    pub hint: Option<String>,
+}
+
+impl ApiError {
+    pub fn title(&self) -> &'static str {
+        match self {
+            ApiError::NotFound => "Not found",
+            ApiError::Network(_) => "Network error",
+            ApiError::Timeout(_) => "Timed out",
+            ApiError::Parse(_) => "Unexpected response",
+            ApiError::QuotaExceeded(_) => "Quota exceeded",
+            ApiError::RateLimited(..) => "Rate limited",
+            ApiError::MissingKey(_) => "Missing API key",
+            ApiError::HttpError(_) => "HTTP error",
+            ApiError::IoError(_) => "I/O error",
+            ApiError::ProtocolError(_) => "Protocol error",
+            ApiError::SpellingError(_) => "Spelling error",
+            ApiError::KeyError(_) => "API key error",
+            ApiError::ConfigError(_) => "Configuration error",
+        }
+    }
+
+    /// Suggests what the user can do about the error.
+    pub fn hint(&self) -> Option<String> {
+        let hint = match self {
+            ApiError::NotFound => {
+                "Check the spelling, or turn spelling suggestions on in the settings screen (:)."
+            }
+            ApiError::Network(_) => {
+                "Check your internet connection, or the server and proxy settings in the config file."
+            }
+            ApiError::Timeout(_) => {
+                "Try again, or raise timeout_secs under [http] in the config file."
+            }
+            ApiError::Parse(_) => {
+                "Check that the server in the config file is a dictionary server of the right kind."
+            }
+            ApiError::QuotaExceeded(_) => {
+                "Wait a while before searching again, or check the limits of your plan."
+            }
+            ApiError::ConfigError(_) => "Fix the setting in the config file.",
+            ApiError::RateLimited(_, wait) => {
+                return Some(format!("Wait {}s before searching again.", wait.as_secs()));
+            }
+            ApiError::MissingKey(service) => {
+                return Some(
+                    format!(
+                        "Set ${} or enter a key in the settings screen (:).",
+                        api_key::env_var(service)
+                    )
+                );
+            }
+            _ => {
+                return None;
+            }
+        };
+        Some(hint.to_string())
+    }
+
+    pub fn report(&self) -> ErrorReport {
+        ErrorReport { title: self.title().to_string(), message: self.to_string(), hint: self.hint() }
+    }
 }
 
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::NotFound => write!(f, "No entry found"),
+            ApiError::Network(msg) => write!(f, "{}", msg),
+            ApiError::Timeout(msg) => write!(f, "{}", msg),
+            ApiError::Parse(msg) => write!(f, "{}", msg),
+            ApiError::QuotaExceeded(host) => write!(f, "{} is refusing requests because too many were made", host),
+            ApiError::RateLimited(host, _) => write!(f, "{} asked for a pause because too many requests were made", host),
+            ApiError::MissingKey(service) => write!(f, "No {} key is set", service),
+            ApiError::HttpError(err) => write!(f, "{}", err),
+            ApiError::IoError(err) => write!(f, "{}", err),
+            ApiError::ProtocolError(msg) => write!(f, "{}", msg),
+            ApiError::SpellingError(msg) => write!(f, "{}", msg),
+            ApiError::KeyError(msg) => write!(f, "{}", msg),
+            ApiError::ConfigError(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
 impl From<reqwest::Error> for ApiError {
     fn from(err: reqwest::Error) -> Self {
-        ApiError::HttpError(err)
+        let host = err
+            .url()
+            .and_then(|url| url.host_str())
+            .unwrap_or("the server")
+            .to_string();
+        if err.is_timeout() {
+            ApiError::Timeout(format!("{} took too long to respond", host))
+        } else if err.is_connect() {
+            ApiError::Network(format!("Cannot reach {}", host))
+        } else if err.is_decode() {
+            ApiError::Parse(format!("Unexpected response from {}: {}", host, err))
+        } else {
+            ApiError::HttpError(err)
+        }
+    }
+}
+
+impl From<std::io::Error> for ApiError {
+    fn from(err: std::io::Error) -> Self {
+        ApiError::IoError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_report() {
+        let report = ApiError::MissingKey(String::from("serpapi")).report();
+        assert_eq!("Missing API key", report.title);
+        assert_eq!("No serpapi key is set", report.message);
+        assert_eq!(
+            Some(String::from("Set $THESAURUST_SERPAPI_KEY or enter a key in the settings screen (:).")),
+            report.hint
+        );
+        assert_eq!(None, ApiError::ProtocolError(String::from("bad reply")).report().hint);
     }
 }
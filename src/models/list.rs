@@ -1,23 +1,19 @@
 use ratatui::widgets::ListState;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub enum StatefulListType {
     PartOfSpeech,
     Definition,
     Synonym,
+    #[default]
     All,
 }
 
-impl Default for StatefulListType {
-    fn default() -> Self {
-        StatefulListType::All
-    }
-}
-
 #[derive(Clone, Debug, Default)]
 pub struct StatefulList<T> {
     pub state: ListState,
     pub items: Vec<T>,
+    #[allow(dead_code)]
     pub list_type: StatefulListType,
 }
 
@@ -26,7 +22,7 @@ impl<T> StatefulList<T> {
         StatefulList {
             state: ListState::default(),
             items,
-            list_type: list_type,
+            list_type,
         }
     }
 
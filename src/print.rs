@@ -0,0 +1,491 @@
+//! Formats entries as text or JSON for the subcommands that run without the interface.
+
+use std::{ env, fmt::Write, io::{ self, IsTerminal } };
+
+use crossterm::style::{ ContentStyle, Stylize };
+use serde_derive::Serialize;
+
+use crate::cache::Origin;
+use crate::client::WordInfo;
+use crate::models::{
+    data::{ Definition, License, Meaning, Phonetic, Thesaurus },
+    errors::{ ApiError, ErrorReport },
+};
+
+/// Which parts of the entries to show.
+#[derive(Clone, Debug, Default)]
+pub struct Filter {
+    /// Only show meanings with this part of speech, such as `noun`.
+    pub part_of_speech: Option<String>,
+    /// The most definitions to show for each part of speech.
+    pub limit: Option<usize>,
+}
+
+impl Filter {
+    fn matches(&self, part_of_speech: Option<&str>) -> bool {
+        match &self.part_of_speech {
+            Some(wanted) => part_of_speech.is_some_and(|pos| pos.eq_ignore_ascii_case(wanted.trim())),
+            None => true,
+        }
+    }
+
+    /// Keeps only the meanings and definitions of `results` that pass the filter, reporting
+    /// the word as not found if none do.
+    pub fn apply(&self, word: &str, mut results: WordInfo) -> WordInfo {
+        let mut entries = std::mem::take(&mut results.t);
+        for entry in entries.iter_mut() {
+            let meanings = entry.meanings.take().unwrap_or_default();
+            let meanings: Vec<Meaning> = meanings
+                .into_iter()
+                .filter(|meaning| self.matches(meaning.partOfSpeech.as_deref()))
+                .map(|mut meaning| {
+                    if let (Some(limit), Some(definitions)) = (self.limit, meaning.definitions.as_mut()) {
+                        definitions.truncate(limit);
+                    }
+                    meaning
+                })
+                .collect();
+            entry.meanings = Some(meanings);
+        }
+        entries.retain(|entry| entry.meanings.as_ref().is_some_and(|meanings| !meanings.is_empty()));
+        if entries.is_empty() && results.error.is_none() {
+            let pos = self.part_of_speech.as_deref().map(|pos| format!("{} ", pos.trim())).unwrap_or_default();
+            results.error = Some(ErrorReport {
+                message: format!("No {}entry found for \"{}\"", pos, word.trim()),
+                ..ApiError::NotFound.report()
+            });
+        }
+        results.t = entries;
+        results
+    }
+}
+
+/// The version of the JSON that `--json` prints. It only changes when a field is removed
+/// or changes meaning; fields may be added without changing it.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// What `--json` prints for a word. It is built from the results rather than serializing
+/// them, so that changes to the models do not change the schema.
+#[derive(Serialize)]
+pub struct Output<'a> {
+    pub schema_version: u32,
+    /// The word as it was asked for.
+    pub word: &'a str,
+    /// Whether the entries came from the cache, the network or a local dictionary.
+    pub source: Option<&'static str>,
+    /// Says when the entries are for another form of the word.
+    pub notice: Option<&'a str>,
+    pub entries: Vec<EntryV1<'a>>,
+    /// Why nothing was found. Entries are empty when it is set.
+    pub error: Option<ErrorV1<'a>>,
+}
+
+impl<'a> Output<'a> {
+    pub fn new(word: &'a str, results: &'a WordInfo) -> Self {
+        Output {
+            schema_version: SCHEMA_VERSION,
+            word,
+            source: results.origin.map(|origin| {
+                match origin {
+                    Origin::Cache => "cache",
+                    Origin::Network => "network",
+                    Origin::Local => "local",
+                }
+            }),
+            notice: results.notice.as_deref(),
+            entries: results.t.iter().map(EntryV1::new).collect(),
+            error: results.error.as_ref().map(|error| ErrorV1 {
+                title: &error.title,
+                message: &error.message,
+                hint: error.hint.as_deref(),
+            }),
+        }
+    }
+}
+
+/// An entry in version 1 of the schema, in the format of the Free Dictionary API.
+/// Optional fields that the source left out are not printed.
+#[derive(Serialize)]
+#[allow(non_snake_case)]
+pub struct EntryV1<'a> {
+    pub word: Option<&'a str>,
+    pub phonetic: Option<&'a str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub phonetics: Vec<PhoneticV1<'a>>,
+    /// Where the word comes from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub origin: Option<&'a str>,
+    pub meanings: Vec<MeaningV1<'a>>,
+    /// Pages that the entry was taken from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sourceUrls: Option<&'a [String]>,
+    /// The license that the entry is published under, which may require attribution.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<LicenseV1<'a>>,
+}
+
+impl<'a> EntryV1<'a> {
+    fn new(entry: &'a Thesaurus) -> Self {
+        EntryV1 {
+            word: entry.word.as_deref(),
+            phonetic: entry.phonetic.as_deref(),
+            phonetics: entry.phonetics.iter().flatten().map(PhoneticV1::new).collect(),
+            origin: entry.origin.as_deref(),
+            meanings: entry.meanings.iter().flatten().map(MeaningV1::new).collect(),
+            sourceUrls: entry.sourceUrls.as_deref(),
+            license: entry.license.as_ref().map(LicenseV1::new),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[allow(non_snake_case)]
+pub struct PhoneticV1<'a> {
+    pub text: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub audio: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sourceUrl: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub license: Option<LicenseV1<'a>>,
+}
+
+impl<'a> PhoneticV1<'a> {
+    fn new(phonetic: &'a Phonetic) -> Self {
+        PhoneticV1 {
+            text: phonetic.text.as_deref(),
+            audio: phonetic.audio.as_deref(),
+            sourceUrl: phonetic.sourceUrl.as_deref(),
+            license: phonetic.license.as_ref().map(LicenseV1::new),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct LicenseV1<'a> {
+    pub name: Option<&'a str>,
+    pub url: Option<&'a str>,
+}
+
+impl<'a> LicenseV1<'a> {
+    fn new(license: &'a License) -> Self {
+        LicenseV1 { name: license.name.as_deref(), url: license.url.as_deref() }
+    }
+}
+
+#[derive(Serialize)]
+#[allow(non_snake_case)]
+pub struct MeaningV1<'a> {
+    pub partOfSpeech: Option<&'a str>,
+    pub definitions: Vec<DefinitionV1<'a>>,
+    pub synonyms: &'a [String],
+    pub antonyms: &'a [String],
+}
+
+impl<'a> MeaningV1<'a> {
+    fn new(meaning: &'a Meaning) -> Self {
+        MeaningV1 {
+            partOfSpeech: meaning.partOfSpeech.as_deref(),
+            definitions: meaning.definitions.iter().flatten().map(DefinitionV1::new).collect(),
+            synonyms: meaning.synonyms.as_deref().unwrap_or_default(),
+            antonyms: meaning.antonyms.as_deref().unwrap_or_default(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DefinitionV1<'a> {
+    pub definition: Option<&'a str>,
+    pub example: Option<&'a str>,
+    pub synonyms: &'a [String],
+    pub antonyms: &'a [String],
+    /// The provider that the definition came from, when several are combined.
+    pub source: Option<&'a str>,
+}
+
+impl<'a> DefinitionV1<'a> {
+    fn new(definition: &'a Definition) -> Self {
+        DefinitionV1 {
+            definition: definition.definition.as_deref(),
+            example: definition.example.as_deref(),
+            synonyms: definition.synonyms.as_deref().unwrap_or_default(),
+            antonyms: definition.antonyms.as_deref().unwrap_or_default(),
+            source: definition.source.as_deref(),
+        }
+    }
+}
+
+/// Why nothing was found, in version 1 of the schema.
+#[derive(Serialize)]
+pub struct ErrorV1<'a> {
+    pub title: &'a str,
+    pub message: &'a str,
+    pub hint: Option<&'a str>,
+}
+
+/// The words that `thesaurust syn` and `thesaurust ant` list.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Relation {
+    Synonyms,
+    Antonyms,
+}
+
+impl Relation {
+    fn of_definition(self, definition: &Definition) -> &[String] {
+        let words = match self {
+            Relation::Synonyms => &definition.synonyms,
+            Relation::Antonyms => &definition.antonyms,
+        };
+        words.as_deref().unwrap_or_default()
+    }
+
+    fn of_meaning(self, meaning: &Meaning) -> &[String] {
+        let words = match self {
+            Relation::Synonyms => &meaning.synonyms,
+            Relation::Antonyms => &meaning.antonyms,
+        };
+        words.as_deref().unwrap_or_default()
+    }
+}
+
+/// Lists the synonyms or antonyms in `entries`: those of each definition, then those of its
+/// whole meaning. `definition` picks one definition of each meaning, counting from 1, and
+/// leaves out the words of the whole meaning, which are not tied to it. Words
+/// that several senses share are listed for each unless `dedupe` is set, which ignores case.
+pub fn related(entries: &[Thesaurus], relation: Relation, definition: Option<usize>, dedupe: bool) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    for meaning in entries.iter().flat_map(|entry| entry.meanings.iter().flatten()) {
+        let definitions = meaning.definitions.as_deref().unwrap_or_default();
+        let mut sense: Vec<&String> = Vec::new();
+        for (i, d) in definitions.iter().enumerate() {
+            if definition.is_none_or(|n| n == i + 1) {
+                sense.extend(relation.of_definition(d));
+            }
+        }
+        // Words of the whole meaning are not tied to any one of its definitions.
+        if definition.is_none() {
+            sense.extend(relation.of_meaning(meaning));
+        }
+        for word in sense {
+            if !dedupe || !words.iter().any(|w| w.eq_ignore_ascii_case(word)) {
+                words.push(word.clone());
+            }
+        }
+    }
+    words
+}
+
+/// Writes `text` to stdout. A reader that has gone away, such as `head`, is not an error.
+pub fn emit(text: &str) -> io::Result<()> {
+    use std::io::Write;
+
+    let mut stdout = io::stdout().lock();
+    match stdout.write_all(text.as_bytes()).and_then(|_| stdout.flush()) {
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => result,
+    }
+}
+
+/// Prints with colors unless they are turned off, `$NO_COLOR` is set or the output is not a terminal.
+pub struct Printer {
+    color: bool,
+}
+
+impl Printer {
+    /// A printer for stdout.
+    pub fn new(no_color: bool) -> Self {
+        Printer { color: !no_color && env::var_os("NO_COLOR").is_none() && io::stdout().is_terminal() }
+    }
+
+    /// A printer for stderr, which may be a terminal when stdout is redirected, or the other way round.
+    pub fn stderr(no_color: bool) -> Self {
+        Printer { color: !no_color && env::var_os("NO_COLOR").is_none() && io::stderr().is_terminal() }
+    }
+
+    fn paint(&self, text: &str, style: ContentStyle) -> String {
+        if self.color {
+            style.apply(text).to_string()
+        } else {
+            text.to_string()
+        }
+    }
+
+    pub fn entries(&self, entries: &[Thesaurus]) -> String {
+        let mut out = String::new();
+        for entry in entries {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            let word = entry.word.as_deref().unwrap_or_default();
+            let _ = write!(out, "{}", self.paint(word, ContentStyle::new().bold()));
+            if let Some(phonetic) = &entry.phonetic {
+                let _ = write!(out, " {}", self.paint(phonetic, ContentStyle::new().dark_grey()));
+            }
+            out.push('\n');
+            for meaning in entry.meanings.iter().flatten() {
+                let part_of_speech = meaning.partOfSpeech.as_deref().unwrap_or_default();
+                let _ = writeln!(out, "\n{}", self.paint(part_of_speech, ContentStyle::new().cyan().italic()));
+                for (i, definition) in meaning.definitions.iter().flatten().enumerate() {
+                    self.definition(&mut out, i + 1, definition);
+                }
+                self.words(&mut out, "  ", "Synonyms", meaning.synonyms.as_deref());
+                self.words(&mut out, "  ", "Antonyms", meaning.antonyms.as_deref());
+            }
+        }
+        out
+    }
+
+    fn definition(&self, out: &mut String, number: usize, definition: &Definition) {
+        let text = definition.definition.as_deref().unwrap_or_default();
+        let _ = writeln!(out, "  {}. {}", number, text);
+        let indent = " ".repeat(number.to_string().len() + 4);
+        if let Some(example) = &definition.example {
+            let example = format!("\"{}\"", example);
+            let _ = writeln!(out, "{}{}", indent, self.paint(&example, ContentStyle::new().dark_grey().italic()));
+        }
+        self.words(out, &indent, "Synonyms", definition.synonyms.as_deref());
+        self.words(out, &indent, "Antonyms", definition.antonyms.as_deref());
+    }
+
+    fn words(&self, out: &mut String, indent: &str, label: &str, words: Option<&[String]>) {
+        if let Some(words) = words.filter(|words| !words.is_empty()) {
+            let label = format!("{}:", label);
+            let _ = writeln!(out, "{}{} {}", indent, self.paint(&label, ContentStyle::new().green()), words.join(", "));
+        }
+    }
+
+    /// Formats an error as it is shown in the error panel.
+    pub fn error(&self, report: &ErrorReport) -> String {
+        let mut out = format!("{}: {}", self.paint(&report.title, ContentStyle::new().red().bold()), report.message);
+        if let Some(hint) = &report.hint {
+            let _ = write!(out, "\n{}", self.paint(hint, ContentStyle::new().yellow()));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn entry() -> Thesaurus {
+        let definition = |text: &str, example: Option<&str>| Definition {
+            definition: Some(text.to_string()),
+            example: example.map(String::from),
+            ..Definition::default()
+        };
+        Thesaurus {
+            word: Some(String::from("hello")),
+            phonetic: Some(String::from("/həˈləʊ/")),
+            meanings: Some(
+                vec![
+                    Meaning {
+                        partOfSpeech: Some(String::from("noun")),
+                        definitions: Some(
+                            vec![
+                                definition("A greeting.", Some("She said hello.")),
+                                definition("An utterance of hello.", None)
+                            ]
+                        ),
+                        synonyms: Some(vec![String::from("greeting"), String::from("salutation")]),
+                        ..Meaning::default()
+                    },
+                    Meaning {
+                        partOfSpeech: Some(String::from("verb")),
+                        definitions: Some(vec![definition("To greet with hello.", None)]),
+                        ..Meaning::default()
+                    }
+                ]
+            ),
+            ..Thesaurus::default()
+        }
+    }
+
+    fn hello() -> WordInfo {
+        WordInfo { t: vec![entry()], ..WordInfo::default() }
+    }
+
+    #[test]
+    fn test_entries() {
+        let printer = Printer { color: false };
+        let filter = Filter { part_of_speech: Some(String::from("Noun")), limit: Some(1) };
+        let results = filter.apply("hello", hello());
+        assert_eq!(
+            "hello /həˈləʊ/\n\nnoun\n  1. A greeting.\n     \"She said hello.\"\n  Synonyms: greeting, salutation\n",
+            printer.entries(&results.t)
+        );
+        let filter = Filter { part_of_speech: Some(String::from("adjective")), limit: None };
+        let results = filter.apply("hello", hello());
+        assert!(results.t.is_empty());
+        assert_eq!("No adjective entry found for \"hello\"", results.error.unwrap().message);
+    }
+
+    #[test]
+    fn test_related() {
+        let mut entry = entry();
+        if let Some(meanings) = entry.meanings.as_mut() {
+            meanings[0].definitions.as_mut().unwrap()[0].synonyms = Some(vec![String::from("hi")]);
+            meanings[0].definitions.as_mut().unwrap()[1].synonyms = Some(vec![String::from("Greeting")]);
+            meanings[1].antonyms = Some(vec![String::from("ignore")]);
+        }
+        let entries = [entry];
+        assert_eq!(
+            vec!["hi", "Greeting", "greeting", "salutation"],
+            related(&entries, Relation::Synonyms, None, false)
+        );
+        assert_eq!(vec!["hi", "Greeting", "salutation"], related(&entries, Relation::Synonyms, None, true));
+        assert_eq!(vec!["hi"], related(&entries, Relation::Synonyms, Some(1), false));
+        assert_eq!(vec!["Greeting"], related(&entries, Relation::Synonyms, Some(2), false));
+        assert_eq!(vec!["ignore"], related(&entries, Relation::Antonyms, None, false));
+        assert!(related(&entries, Relation::Antonyms, Some(1), false).is_empty());
+    }
+
+    #[test]
+    fn test_json_output() {
+        let filter = Filter { part_of_speech: Some(String::from("verb")), limit: None };
+        let results = filter.apply("hello", hello());
+        let json = serde_json::to_value(Output::new("hello", &results)).unwrap();
+        assert_eq!(SCHEMA_VERSION, json["schema_version"]);
+        assert_eq!("hello", json["word"]);
+        assert!(json["source"].is_null());
+        assert_eq!("verb", json["entries"][0]["meanings"][0]["partOfSpeech"]);
+        assert_eq!("To greet with hello.", json["entries"][0]["meanings"][0]["definitions"][0]["definition"]);
+        assert_eq!(0, json["entries"][0]["meanings"][0]["synonyms"].as_array().unwrap().len());
+        assert!(json["error"].is_null());
+
+        let results = WordInfo { origin: Some(Origin::Local), ..hello() };
+        let json = serde_json::to_value(Output::new("hello", &results)).unwrap();
+        assert_eq!("local", json["source"]);
+
+        let results = WordInfo { error: Some(ApiError::NotFound.report()), ..WordInfo::default() };
+        let json = serde_json::to_value(Output::new("zzz", &results)).unwrap();
+        assert_eq!("Not found", json["error"]["title"]);
+        assert_eq!(0, json["entries"].as_array().unwrap().len());
+    }
+
+    #[test]
+    fn test_json_output_keeps_attribution() {
+        let entry = Thesaurus {
+            phonetics: Some(vec![Phonetic { text: Some(String::from("/həˈləʊ/")), ..Phonetic::default() }]),
+            sourceUrls: Some(vec![String::from("https://en.wiktionary.org/wiki/hello")]),
+            license: Some(License {
+                name: Some(String::from("CC BY-SA 4.0")),
+                url: Some(String::from("https://creativecommons.org/licenses/by-sa/4.0")),
+            }),
+            ..entry()
+        };
+        let results = WordInfo { t: vec![entry], ..WordInfo::default() };
+        let json = serde_json::to_value(Output::new("hello", &results)).unwrap();
+        let entry = &json["entries"][0];
+        assert_eq!("https://en.wiktionary.org/wiki/hello", entry["sourceUrls"][0]);
+        assert_eq!("CC BY-SA 4.0", entry["license"]["name"]);
+        assert_eq!("https://creativecommons.org/licenses/by-sa/4.0", entry["license"]["url"]);
+        assert_eq!("/həˈləʊ/", entry["phonetics"][0]["text"]);
+        assert!(entry["phonetics"][0].get("audio").is_none());
+        assert!(entry.get("origin").is_none());
+
+        let json = serde_json::to_value(Output::new("hello", &hello())).unwrap();
+        assert!(json["entries"][0].get("license").is_none());
+    }
+}
@@ -0,0 +1,337 @@
+use async_trait::async_trait;
+
+use crate::models::{ data::{ Definition, Thesaurus }, errors::ApiError };
+use crate::providers::{ Database, DictionaryProvider, LookupOptions, Provider };
+
+/// Looks words up from several providers in order. It either returns the first provider
+/// that finds the word, or merges what every provider found.
+#[derive(Clone, Debug)]
+pub struct Chain {
+    name: String,
+    providers: Vec<Provider>,
+    merge: bool,
+}
+
+impl Chain {
+    pub fn new(providers: Vec<Provider>, merge: bool) -> Self {
+        // Merged results differ from the first ones found, so they are named, and cached, apart.
+        let name = providers
+            .iter()
+            .map(|p| p.name().to_string())
+            .collect::<Vec<String>>()
+            .join(if merge { "&" } else { "+" });
+        Chain { name, providers, merge }
+    }
+}
+
+#[async_trait]
+impl DictionaryProvider for Chain {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn lookup(&self, word: &str, options: &LookupOptions) -> Result<Vec<Thesaurus>, ApiError> {
+        let mut last_error = ApiError::NotFound;
+        let mut merged: Option<Thesaurus> = None;
+        for provider in &self.providers {
+            match provider.lookup(word, options).await {
+                Ok(mut t) => {
+                    tag_source(&mut t, provider.name());
+                    if !self.merge {
+                        return Ok(t);
+                    }
+                    for thesaurus in t {
+                        match merged.as_mut() {
+                            Some(merged) => merge_into(merged, thesaurus),
+                            None => {
+                                merged = Some(thesaurus);
+                            }
+                        }
+                    }
+                }
+                Err(err) => {
+                    last_error = err;
+                }
+            }
+        }
+        merged.map(|t| vec![t]).ok_or(last_error)
+    }
+
+    /// Asks each provider in turn, so that one that fails, such as for want of an API key,
+    /// does not keep the others from suggesting. Its error is returned if none suggests anything.
+    async fn suggest(&self, word: &str, options: &LookupOptions) -> Result<Option<String>, ApiError> {
+        let mut last_error = None;
+        for provider in &self.providers {
+            match provider.suggest(word, options).await {
+                Ok(Some(suggestion)) => {
+                    return Ok(Some(suggestion));
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    last_error = Some(err);
+                }
+            }
+        }
+        last_error.map_or(Ok(None), Err)
+    }
+
+    async fn suggestions(&self, word: &str, options: &LookupOptions) -> Result<Vec<String>, ApiError> {
+        let mut last_error = None;
+        for provider in &self.providers {
+            match provider.suggestions(word, options).await {
+                Ok(suggestions) if !suggestions.is_empty() => {
+                    return Ok(suggestions);
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    last_error = Some(err);
+                }
+            }
+        }
+        last_error.map_or(Ok(Vec::new()), Err)
+    }
+
+    fn languages(&self) -> Vec<String> {
+        let mut languages: Vec<String> = Vec::new();
+        for language in self.providers.iter().flat_map(|p| p.languages()) {
+            if !languages.contains(&language) {
+                languages.push(language);
+            }
+        }
+        languages
+    }
+
+    fn is_local(&self, options: &LookupOptions) -> bool {
+        self.providers.iter().all(|p| p.is_local(options))
+    }
+
+    fn api_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = Vec::new();
+        for key in self.providers.iter().flat_map(|p| p.api_keys()) {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        keys
+    }
+
+    async fn databases(&self) -> Result<Vec<Database>, ApiError> {
+        for provider in &self.providers {
+            let databases = provider.databases().await?;
+            if !databases.is_empty() {
+                return Ok(databases);
+            }
+        }
+        Ok(Vec::new())
+    }
+}
+
+/// Records `source` on every definition that does not say where it came from yet.
+fn tag_source(results: &mut [Thesaurus], source: &str) {
+    let definitions = results
+        .iter_mut()
+        .flat_map(|t| t.meanings.iter_mut().flatten())
+        .flat_map(|m| m.definitions.iter_mut().flatten());
+    for definition in definitions {
+        definition.source.get_or_insert_with(|| source.to_string());
+    }
+}
+
+/// Adds the words that `into` does not have yet, ignoring case.
+fn union(into: &mut Option<Vec<String>>, words: Option<Vec<String>>) {
+    let into = into.get_or_insert_with(Vec::new);
+    for word in words.into_iter().flatten() {
+        if !into.iter().any(|w| w.eq_ignore_ascii_case(&word)) {
+            into.push(word);
+        }
+    }
+}
+
+fn same_text(a: &Option<String>, b: &Option<String>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => a.trim().eq_ignore_ascii_case(b.trim()),
+        _ => false,
+    }
+}
+
+fn merge_definition(definitions: &mut Vec<Definition>, definition: Definition) {
+    match definitions.iter_mut().find(|d| same_text(&d.definition, &definition.definition)) {
+        Some(existing) => {
+            if existing.example.is_none() {
+                existing.example = definition.example;
+            }
+            union(&mut existing.synonyms, definition.synonyms);
+            union(&mut existing.antonyms, definition.antonyms);
+        }
+        None => definitions.push(definition),
+    }
+}
+
+/// Merges `other` into `merged`, combining meanings that share a part of speech.
+fn merge_into(merged: &mut Thesaurus, other: Thesaurus) {
+    if merged.origin.is_none() {
+        merged.origin = other.origin;
+    }
+    if merged.phonetic.is_none() {
+        merged.phonetic = other.phonetic;
+    }
+    if merged.phonetics.is_none() {
+        merged.phonetics = other.phonetics;
+    }
+    if merged.license.is_none() {
+        merged.license = other.license;
+    }
+    union(&mut merged.sourceUrls, other.sourceUrls);
+    let meanings = merged.meanings.get_or_insert_with(Vec::new);
+    for meaning in other.meanings.into_iter().flatten() {
+        match meanings.iter_mut().find(|m| same_text(&m.partOfSpeech, &meaning.partOfSpeech)) {
+            Some(existing) => {
+                union(&mut existing.synonyms, meaning.synonyms);
+                union(&mut existing.antonyms, meaning.antonyms);
+                let definitions = existing.definitions.get_or_insert_with(Vec::new);
+                for definition in meaning.definitions.into_iter().flatten() {
+                    merge_definition(definitions, definition);
+                }
+            }
+            None => meanings.push(meaning),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::data::Meaning;
+    use pretty_assertions::assert_eq;
+
+    /// A provider that always returns the same entry.
+    struct Static {
+        name: &'static str,
+        results: Option<Vec<Thesaurus>>,
+    }
+
+    #[async_trait]
+    impl DictionaryProvider for Static {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn lookup(&self, _word: &str, _options: &LookupOptions) -> Result<Vec<Thesaurus>, ApiError> {
+            self.results.clone().ok_or(ApiError::NotFound)
+        }
+    }
+
+    /// A provider that suggests `spelling`, or fails for want of an API key without one.
+    struct Speller {
+        spelling: Option<&'static str>,
+    }
+
+    #[async_trait]
+    impl DictionaryProvider for Speller {
+        fn name(&self) -> &str {
+            "speller"
+        }
+
+        async fn lookup(&self, _word: &str, _options: &LookupOptions) -> Result<Vec<Thesaurus>, ApiError> {
+            Err(ApiError::NotFound)
+        }
+
+        async fn suggest(&self, _word: &str, _options: &LookupOptions) -> Result<Option<String>, ApiError> {
+            match self.spelling {
+                Some(spelling) => Ok(Some(spelling.to_string())),
+                None => Err(ApiError::MissingKey(String::from("serpapi"))),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_suggestions_skip_failing_providers() {
+        let options = LookupOptions::default();
+        let failing = || Provider::new(Speller { spelling: None });
+        let chain = Chain::new(vec![failing(), Provider::new(Speller { spelling: Some("happy") })], false);
+        assert_eq!(Some(String::from("happy")), chain.suggest("hapy", &options).await.unwrap());
+        assert_eq!(vec![String::from("happy")], chain.suggestions("hapy", &options).await.unwrap());
+
+        let chain = Chain::new(vec![failing(), failing()], false);
+        assert!(matches!(chain.suggest("hapy", &options).await, Err(ApiError::MissingKey(_))));
+    }
+
+    fn definition(text: &str, synonyms: &[&str]) -> Definition {
+        Definition {
+            definition: Some(text.to_string()),
+            synonyms: Some(synonyms.iter().map(|s| s.to_string()).collect()),
+            ..Definition::default()
+        }
+    }
+
+    fn entry(meanings: Vec<(&str, Vec<Definition>)>) -> Option<Vec<Thesaurus>> {
+        Some(
+            vec![Thesaurus {
+                word: Some(String::from("happy")),
+                meanings: Some(
+                    meanings
+                        .into_iter()
+                        .map(|(pos, definitions)| Meaning {
+                            partOfSpeech: Some(pos.to_string()),
+                            definitions: Some(definitions),
+                            ..Meaning::default()
+                        })
+                        .collect()
+                ),
+                ..Thesaurus::default()
+            }]
+        )
+    }
+
+    fn providers() -> Vec<Provider> {
+        vec![
+            Provider::new(Static { name: "glossary", results: None }),
+            Provider::new(Static {
+                name: "wordnet",
+                results: entry(vec![("adjective", vec![definition("Feeling joy.", &["glad"])])]),
+            }),
+            Provider::new(Static {
+                name: "free-dictionary",
+                results: entry(
+                    vec![
+                        ("adjective", vec![definition("feeling joy.", &["Glad", "content"])]),
+                        ("noun", vec![definition("A happy person.", &[])])
+                    ]
+                ),
+            })
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_first_hit() {
+        let chain = Chain::new(providers(), false);
+        assert_eq!("glossary+wordnet+free-dictionary", chain.name());
+        let results = chain.lookup("happy", &LookupOptions::default()).await.unwrap();
+        let meanings = results[0].meanings.clone().unwrap();
+        assert_eq!(1, meanings.len());
+        let definitions = meanings[0].definitions.clone().unwrap();
+        assert_eq!(Some(String::from("wordnet")), definitions[0].source);
+    }
+
+    #[tokio::test]
+    async fn test_merge() {
+        let chain = Chain::new(providers(), true);
+        assert_eq!("glossary&wordnet&free-dictionary", chain.name());
+        let results = chain.lookup("happy", &LookupOptions::default()).await.unwrap();
+        assert_eq!(1, results.len());
+        let meanings = results[0].meanings.clone().unwrap();
+        assert_eq!(2, meanings.len());
+        let adjectives = meanings[0].definitions.clone().unwrap();
+        assert_eq!(1, adjectives.len());
+        assert_eq!(Some(vec![String::from("glad"), String::from("content")]), adjectives[0].synonyms);
+        assert_eq!(Some(String::from("wordnet")), adjectives[0].source);
+        let nouns = meanings[1].definitions.clone().unwrap();
+        assert_eq!(Some(String::from("free-dictionary")), nouns[0].source);
+    }
+
+    #[tokio::test]
+    async fn test_no_hits() {
+        let chain = Chain::new(vec![Provider::new(Static { name: "glossary", results: None })], true);
+        assert!(chain.lookup("happy", &LookupOptions::default()).await.is_err());
+    }
+}
@@ -0,0 +1,398 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::{ io::{ AsyncBufReadExt, AsyncWriteExt, BufReader }, net::TcpStream, time };
+
+use crate::models::{ data::Thesaurus, errors::ApiError };
+use crate::providers::{ text::parse_text, Database, DictionaryProvider, LookupOptions };
+
+/// Searches every database on the server.
+pub const ALL_DATABASES: &str = "*";
+
+/// Looks words up from a dictd server over the DICT protocol (RFC 2229).
+#[derive(Clone, Debug)]
+pub struct Dictd {
+    address: String,
+    /// Whether the server runs on this machine.
+    local: bool,
+    database: String,
+    strategy: String,
+    connect_timeout: Duration,
+    /// How long to wait for each line of a reply.
+    timeout: Duration,
+}
+
+/// A definition returned by `DEFINE`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DictDefinition {
+    pub database: String,
+    pub description: String,
+    pub text: String,
+}
+
+impl Dictd {
+    /// `database` is used unless a lookup asks for another one, and `strategy` is the
+    /// `MATCH` strategy used to suggest spellings.
+    pub fn new(host: &str, port: u16, database: &str, strategy: &str) -> Self {
+        Dictd {
+            address: format!("{}:{}", host, port),
+            local: ["localhost", "127.0.0.1", "::1"].contains(&host.trim_matches(['[', ']'])),
+            database: database.to_string(),
+            strategy: strategy.to_string(),
+            connect_timeout: Duration::from_secs(5),
+            timeout: Duration::from_secs(15),
+        }
+    }
+
+    /// Gives up on connecting after `connect_timeout`, and on a reply when no line of it
+    /// has arrived after `timeout`.
+    pub fn with_timeouts(self, connect_timeout: Duration, timeout: Duration) -> Self {
+        Dictd { connect_timeout, timeout, ..self }
+    }
+
+    async fn connect(&self) -> Result<Connection, ApiError> {
+        let stream = match time::timeout(self.connect_timeout, TcpStream::connect(&self.address)).await {
+            Ok(stream) => stream,
+            Err(_) => {
+                return Err(
+                    ApiError::Timeout(format!("The dict server at {} took too long to answer", self.address))
+                );
+            }
+        };
+        let stream = stream.map_err(|err| {
+            ApiError::Network(format!("Cannot reach the dict server at {}: {}", self.address, err))
+        })?;
+        let mut connection = Connection {
+            stream: BufReader::new(stream),
+            address: self.address.clone(),
+            timeout: self.timeout,
+        };
+        connection.expect(&[220]).await?;
+        Ok(connection)
+    }
+
+    fn database_for<'a>(&'a self, options: &'a LookupOptions) -> &'a str {
+        options.database.as_deref().unwrap_or(&self.database)
+    }
+
+    /// Sends `DEFINE` and returns every definition found.
+    pub async fn define(&self, database: &str, word: &str) -> Result<Vec<DictDefinition>, ApiError> {
+        let mut connection = self.connect().await?;
+        connection.send(&format!("DEFINE {} {}", quote(database), quote(word))).await?;
+        let mut definitions = Vec::new();
+        let (code, _) = connection.expect(&[150, 552]).await?;
+        if code == 150 {
+            loop {
+                let (code, line) = connection.expect(&[151, 250]).await?;
+                if code == 250 {
+                    break;
+                }
+                let mut fields = split_quoted(&line).into_iter().skip(1);
+                let database = fields.next().unwrap_or_default();
+                let description = fields.next().unwrap_or_default();
+                definitions.push(DictDefinition {
+                    database,
+                    description,
+                    text: connection.read_text().await?,
+                });
+            }
+        }
+        connection.quit().await;
+        Ok(definitions)
+    }
+
+    /// Sends `MATCH` and returns the matching headwords.
+    pub async fn find_matches(
+        &self,
+        database: &str,
+        strategy: &str,
+        word: &str
+    ) -> Result<Vec<String>, ApiError> {
+        let mut connection = self.connect().await?;
+        connection.send(&format!("MATCH {} {} {}", quote(database), quote(strategy), quote(word))).await?;
+        let (code, _) = connection.expect(&[152, 552]).await?;
+        let mut matches = Vec::new();
+        if code == 152 {
+            for line in connection.read_text().await?.lines() {
+                if let Some(word) = split_quoted(line).get(1) {
+                    if !matches.contains(word) {
+                        matches.push(word.clone());
+                    }
+                }
+            }
+            connection.expect(&[250]).await?;
+        }
+        connection.quit().await;
+        Ok(matches)
+    }
+
+    /// Sends `SHOW DB` and returns the databases that the server has.
+    pub async fn show_databases(&self) -> Result<Vec<Database>, ApiError> {
+        let mut connection = self.connect().await?;
+        connection.send("SHOW DB").await?;
+        let (code, _) = connection.expect(&[110, 554]).await?;
+        let mut databases = Vec::new();
+        if code == 110 {
+            for line in connection.read_text().await?.lines() {
+                let mut fields = split_quoted(line).into_iter();
+                if let (Some(name), Some(description)) = (fields.next(), fields.next()) {
+                    databases.push(Database { name, description });
+                }
+            }
+            connection.expect(&[250]).await?;
+        }
+        connection.quit().await;
+        Ok(databases)
+    }
+}
+
+#[async_trait]
+impl DictionaryProvider for Dictd {
+    fn name(&self) -> &str {
+        "dict"
+    }
+
+    fn is_local(&self, _options: &LookupOptions) -> bool {
+        self.local
+    }
+
+    async fn lookup(&self, word: &str, options: &LookupOptions) -> Result<Vec<Thesaurus>, ApiError> {
+        let definitions = self.define(self.database_for(options), word.trim()).await?;
+        let meanings: Vec<_> = definitions
+            .iter()
+            .flat_map(|d| {
+                // Most databases repeat the headword on the first line of the definition.
+                let text = match d.text.split_once('\n') {
+                    Some((first, rest)) if first.trim().eq_ignore_ascii_case(word.trim()) => rest,
+                    _ => &d.text,
+                };
+                parse_text(text, &d.database)
+            })
+            .collect();
+        if meanings.is_empty() {
+            return Err(ApiError::NotFound);
+        }
+        Ok(
+            vec![Thesaurus {
+                word: Some(word.trim().to_string()),
+                meanings: Some(meanings),
+                ..Thesaurus::default()
+            }]
+        )
+    }
+
+    async fn suggest(&self, word: &str, options: &LookupOptions) -> Result<Option<String>, ApiError> {
+        let matches = self.find_matches(self.database_for(options), &self.strategy, word.trim()).await?;
+        Ok(matches.into_iter().find(|m| !m.eq_ignore_ascii_case(word.trim())))
+    }
+
+    async fn databases(&self) -> Result<Vec<Database>, ApiError> {
+        self.show_databases().await
+    }
+}
+
+struct Connection {
+    stream: BufReader<TcpStream>,
+    address: String,
+    timeout: Duration,
+}
+
+impl Connection {
+    async fn send(&mut self, command: &str) -> Result<(), ApiError> {
+        self.stream.get_mut().write_all(format!("{}\r\n", command).as_bytes()).await?;
+        Ok(())
+    }
+
+    async fn read_line(&mut self) -> Result<String, ApiError> {
+        let mut line = String::new();
+        let read = time::timeout(self.timeout, self.stream.read_line(&mut line)).await.map_err(|_| {
+            ApiError::Timeout(format!("The dict server at {} took too long to respond", self.address))
+        })?;
+        if read? == 0 {
+            return Err(ApiError::ProtocolError(String::from("dictd closed the connection")));
+        }
+        Ok(line.trim_end_matches(['\r', '\n']).to_string())
+    }
+
+    /// Reads a status line and fails unless its code is one of `codes`.
+    async fn expect(&mut self, codes: &[u16]) -> Result<(u16, String), ApiError> {
+        let line = self.read_line().await?;
+        let code = line
+            .get(..3)
+            .and_then(|code| code.parse().ok())
+            .ok_or_else(|| ApiError::ProtocolError(format!("unexpected reply: {}", line)))?;
+        if !codes.contains(&code) {
+            return Err(ApiError::ProtocolError(line));
+        }
+        Ok((code, line[3..].trim().to_string()))
+    }
+
+    /// Reads a text block that ends with a line containing a single period.
+    async fn read_text(&mut self) -> Result<String, ApiError> {
+        let mut lines = Vec::new();
+        loop {
+            let line = self.read_line().await?;
+            if line == "." {
+                break;
+            }
+            // Lines that start with a period have it doubled on the wire.
+            lines.push(line.strip_prefix('.').map_or(line.clone(), |l| l.to_string()));
+        }
+        Ok(lines.join("\n"))
+    }
+
+    async fn quit(&mut self) {
+        if self.send("QUIT").await.is_ok() {
+            let _ = self.expect(&[221]).await;
+        }
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Splits a line into words, keeping quoted strings together.
+fn split_quoted(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+            }
+            '\\' if in_quotes => word.extend(chars.next()),
+            c if c.is_whitespace() && !in_quotes => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            c => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tokio::net::TcpListener;
+
+    /// Starts a server that answers each command with the scripted reply and returns its port.
+    async fn serve(replies: Vec<(&'static str, &'static str)>) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut stream = BufReader::new(stream);
+            stream.get_mut().write_all(b"220 test dictd <auth.mime> <1@test>\r\n").await.unwrap();
+            for (command, reply) in replies {
+                let mut line = String::new();
+                stream.read_line(&mut line).await.unwrap();
+                assert_eq!(command, line.trim_end());
+                stream.get_mut().write_all(reply.as_bytes()).await.unwrap();
+            }
+        });
+        port
+    }
+
+    #[tokio::test]
+    async fn test_lookup() {
+        let port = serve(
+            vec![
+                (
+                    "DEFINE \"wn\" \"dog\"",
+                    "150 1 definitions retrieved\r\n\
+                     151 \"dog\" wn \"WordNet (r) 3.0 (2006)\"\r\n\
+                     dog\r\n\
+                     \x20   n 1: a member of the genus Canis [syn: {dog}, {domestic dog}]\r\n\
+                     \x20   v 1: go after with the intent to catch [syn: {chase}]\r\n\
+                     .\r\n\
+                     250 ok\r\n",
+                ),
+                ("QUIT", "221 bye\r\n")
+            ]
+        ).await;
+        let dictd = Dictd::new("127.0.0.1", port, "wn", "lev");
+        let results = dictd.lookup("dog", &LookupOptions::default()).await.unwrap();
+        let meanings = results[0].meanings.clone().unwrap();
+        assert_eq!(2, meanings.len());
+        assert_eq!(Some(String::from("noun")), meanings[0].partOfSpeech);
+        assert_eq!(
+            Some(String::from("a member of the genus Canis")),
+            meanings[0].definitions.clone().unwrap()[0].definition
+        );
+    }
+
+    #[tokio::test]
+    async fn test_silent_server_times_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        // Accepts the connection but never greets.
+        tokio::spawn(async move {
+            let (_stream, _) = listener.accept().await.unwrap();
+            time::sleep(Duration::from_secs(5)).await;
+        });
+        let dictd = Dictd::new("127.0.0.1", port, "wn", "lev")
+            .with_timeouts(Duration::from_secs(1), Duration::from_millis(100));
+        let result = dictd.lookup("dog", &LookupOptions::default()).await;
+        assert!(matches!(result, Err(ApiError::Timeout(_))));
+    }
+
+    #[tokio::test]
+    async fn test_lookup_without_match() {
+        let port = serve(
+            vec![("DEFINE \"*\" \"dgo\"", "552 no match\r\n"), ("QUIT", "221 bye\r\n")]
+        ).await;
+        let dictd = Dictd::new("127.0.0.1", port, ALL_DATABASES, "lev");
+        assert!(dictd.lookup("dgo", &LookupOptions::default()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_suggest() {
+        let port = serve(
+            vec![
+                (
+                    "MATCH \"wn\" \"lev\" \"dgo\"",
+                    "152 2 matches found\r\nwn \"dog\"\r\nwn \"ego\"\r\n.\r\n250 ok\r\n",
+                ),
+                ("QUIT", "221 bye\r\n")
+            ]
+        ).await;
+        let dictd = Dictd::new("127.0.0.1", port, ALL_DATABASES, "lev");
+        let options = LookupOptions { database: Some(String::from("wn")), ..LookupOptions::default() };
+        assert_eq!(Some(String::from("dog")), dictd.suggest("dgo", &options).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_show_databases() {
+        let port = serve(
+            vec![
+                (
+                    "SHOW DB",
+                    "110 2 databases present\r\nwn \"WordNet (r) 3.0 (2006)\"\r\ngcide \"The Collaborative International Dictionary of English\"\r\n.\r\n250 ok\r\n",
+                ),
+                ("QUIT", "221 bye\r\n")
+            ]
+        ).await;
+        let dictd = Dictd::new("127.0.0.1", port, ALL_DATABASES, "lev");
+        let databases = dictd.databases().await.unwrap();
+        assert_eq!(2, databases.len());
+        assert_eq!("wn", databases[0].name);
+        assert_eq!("WordNet (r) 3.0 (2006)", databases[0].description);
+    }
+
+    #[test]
+    fn test_split_quoted() {
+        assert_eq!(
+            vec!["151", "dog", "wn", "WordNet (r) 3.0"],
+            split_quoted("151 \"dog\" wn \"WordNet (r) 3.0\"")
+        );
+    }
+}
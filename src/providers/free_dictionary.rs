@@ -0,0 +1,212 @@
+use async_trait::async_trait;
+use reqwest::{ StatusCode, Url };
+
+use crate::api_key::{ self, SERPAPI };
+use crate::http::Http;
+use crate::models::{ data::Thesaurus, errors::ApiError, word_suggestion::SearchResults };
+use crate::providers::{ DictionaryProvider, LookupOptions };
+
+/// The public Free Dictionary API, used unless another server is configured.
+pub const DEFAULT_BASE_URL: &str = "https://api.dictionaryapi.dev/api/v2/entries";
+
+/// The SerpApi endpoint that spellings are suggested from.
+const SERPAPI_URL: &str = "https://serpapi.com/search.json";
+
+/// Languages that the Free Dictionary API has entries for.
+const LANGUAGES: [&str; 12] = ["en", "hi", "es", "fr", "ja", "ru", "de", "it", "ko", "pt-BR", "ar", "tr"];
+
+/// Looks words up from the Free Dictionary API and suggests spellings with SerpApi.
+#[derive(Clone, Debug)]
+pub struct FreeDictionary {
+    base_url: String,
+    http: Http,
+}
+
+impl FreeDictionary {
+    /// `base_url` is the entries endpoint of a Free Dictionary API server,
+    /// such as `http://localhost:3000/api/v2/entries`.
+    pub fn new(base_url: &str, http: Http) -> Self {
+        FreeDictionary { base_url: base_url.trim_end_matches('/').to_string(), http }
+    }
+}
+
+impl Default for FreeDictionary {
+    fn default() -> Self {
+        FreeDictionary::new(DEFAULT_BASE_URL, Http::default())
+    }
+}
+
+#[async_trait]
+impl DictionaryProvider for FreeDictionary {
+    fn name(&self) -> &str {
+        "free-dictionary"
+    }
+
+    async fn lookup(&self, word: &str, options: &LookupOptions) -> Result<Vec<Thesaurus>, ApiError> {
+        let results = search_dictionary(&self.http, &self.base_url, &options.language, word).await?;
+        serde_json::from_value(results).map_err(|err| {
+            ApiError::Parse(format!("Unexpected response from {}: {}", self.base_url, err))
+        })
+    }
+
+    async fn suggest(&self, word: &str, options: &LookupOptions) -> Result<Option<String>, ApiError> {
+        let key = api_key::get(SERPAPI)?.ok_or_else(|| ApiError::MissingKey(SERPAPI.to_string()))?;
+        let suggestion = suggest_spelling(&self.http, &key, &options.language, word).await?;
+        Ok(Some(suggestion).filter(|s| !s.is_empty()))
+    }
+
+    fn languages(&self) -> Vec<String> {
+        LANGUAGES.iter().map(|l| l.to_string()).collect()
+    }
+
+    fn api_keys(&self) -> Vec<String> {
+        vec![SERPAPI.to_string()]
+    }
+}
+
+async fn search_dictionary(
+    http: &Http,
+    base_url: &str,
+    language: &str,
+    word: &str
+) -> Result<serde_json::Value, ApiError> {
+    let url = construct_url(base_url, language, word)?;
+    let response = http.get(&url, &[]).await.map_err(|err| {
+        match err {
+            ApiError::Network(_) => {
+                ApiError::Network(format!("Cannot reach the dictionary server at {}", base_url))
+            }
+            err => err,
+        }
+    })?;
+    if response.status() == StatusCode::NOT_FOUND {
+        return Err(ApiError::NotFound);
+    }
+    let results: serde_json::Value = response.error_for_status()?.json().await?;
+    Ok(results)
+}
+
+async fn suggest_spelling(http: &Http, key: &str, language: &str, word: &str) -> Result<String, ApiError> {
+    let params = [
+        ("engine", "google"),
+        ("q", word),
+        ("hl", language),
+        ("gl", "us"),
+        ("api_key", key),
+    ];
+    let response = http.get(SERPAPI_URL, &params).await?;
+    if response.status() == StatusCode::UNAUTHORIZED {
+        return Err(ApiError::KeyError(String::from("SerpApi rejected the API key")));
+    }
+    let results: serde_json::Value = response.json().await?;
+    if let Some(error) = results["error"].as_str() {
+        if error.contains("run out of searches") {
+            return Err(ApiError::QuotaExceeded(String::from("serpapi.com")));
+        }
+        return Err(ApiError::SpellingError(format!("SerpApi: {}", error)));
+    }
+    let search_information = &results["search_information"];
+
+    let results: SearchResults = serde_json
+        ::from_value(search_information.clone())
+        .unwrap_or(SearchResults { spelling_fix: String::from("") });
+
+    Ok(results.spelling_fix)
+}
+
+/// Adds the language and word to `base_url` as path segments, percent-encoding them so that
+/// words such as `and/or` stay one segment.
+fn construct_url(base_url: &str, language: &str, word: &str) -> Result<String, ApiError> {
+    let invalid = || ApiError::ConfigError(format!("{} is not a valid dictionary server URL", base_url));
+    let mut url = Url::parse(base_url).map_err(|_| invalid())?;
+    url.path_segments_mut()
+        .map_err(|_| invalid())?
+        .pop_if_empty()
+        .extend([language, word]);
+    Ok(url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::HttpConfig;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_construct_url() {
+        let dictionary = FreeDictionary::new("http://localhost:3000/api/v2/entries/", Http::default());
+        assert_eq!(
+            "http://localhost:3000/api/v2/entries/en/hello",
+            construct_url(&dictionary.base_url, "en", "hello").unwrap()
+        );
+        assert_eq!(
+            "http://localhost:3000/api/v2/entries/en/and%2For",
+            construct_url(&dictionary.base_url, "en", "and/or").unwrap()
+        );
+        assert_eq!(
+            "http://localhost:3000/api/v2/entries/en/ice%20cream",
+            construct_url(&dictionary.base_url, "en", "ice cream").unwrap()
+        );
+        assert!(construct_url("not a url", "en", "hello").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_lookup_from_configured_server() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("GET", "/api/v2/entries/en/hello")
+            .with_header("content-type", "application/json")
+            .with_body(r#"[{"word":"hello","meanings":[{"partOfSpeech":"noun","definitions":[]}]}]"#)
+            .create_async().await;
+        let dictionary = FreeDictionary::new(&format!("{}/api/v2/entries", server.url()), Http::default());
+        let results = dictionary.lookup("hello", &LookupOptions::default()).await.unwrap();
+        mock.assert_async().await;
+        assert_eq!(Some(String::from("hello")), results[0].word);
+    }
+
+    #[test]
+    fn test_full_response() {
+        let response = r#"[{
+            "word": "hello",
+            "phonetic": "həˈləʊ",
+            "phonetics": [
+                {"text": "həˈləʊ", "audio": ""},
+                {
+                    "text": "/həˈloʊ/",
+                    "audio": "https://api.dictionaryapi.dev/media/pronunciations/en/hello-us.mp3",
+                    "sourceUrl": "https://commons.wikimedia.org/w/index.php?curid=75797336",
+                    "license": {"name": "BY-SA 4.0", "url": "https://creativecommons.org/licenses/by-sa/4.0"}
+                }
+            ],
+            "meanings": [{
+                "partOfSpeech": "interjection",
+                "definitions": [{"definition": "A greeting.", "synonyms": [], "antonyms": []}],
+                "synonyms": ["greeting"],
+                "antonyms": ["bye", "goodbye"]
+            }],
+            "license": {"name": "CC BY-SA 3.0", "url": "https://creativecommons.org/licenses/by-sa/3.0"},
+            "sourceUrls": ["https://en.wiktionary.org/wiki/hello"]
+        }]"#;
+        let results: Vec<Thesaurus> = serde_json::from_str(response).unwrap();
+        assert_eq!(Some(String::from("həˈləʊ")), results[0].phonetic);
+        let phonetics = results[0].phonetics.clone().unwrap();
+        assert_eq!(Some(String::from("BY-SA 4.0")), phonetics[1].license.clone().unwrap().name);
+        let meaning = results[0].meanings.clone().unwrap()[0].clone();
+        assert_eq!(Some(vec![String::from("bye"), String::from("goodbye")]), meaning.antonyms);
+        assert_eq!(Some(vec![String::from("https://en.wiktionary.org/wiki/hello")]), results[0].sourceUrls);
+        assert_eq!(Some(String::from("CC BY-SA 3.0")), results[0].license.clone().unwrap().name);
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_server() {
+        // Nothing listens on port 1.
+        let http = Http::new(&HttpConfig { max_retries: 0, ..HttpConfig::default() }).unwrap();
+        let dictionary = FreeDictionary::new("http://127.0.0.1:1/api/v2/entries", http);
+        match dictionary.lookup("hello", &LookupOptions::default()).await {
+            Err(ApiError::Network(message)) => {
+                assert_eq!("Cannot reach the dictionary server at http://127.0.0.1:1/api/v2/entries", message);
+            }
+            other => panic!("expected an unreachable server, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use std::{ fmt, ops::Deref, sync::Arc, time::Duration };
+
+use async_trait::async_trait;
+
+use crate::config::{ Config, ProviderKind };
+use crate::http::Http;
+use crate::models::{ data::Thesaurus, errors::ApiError };
+use crate::spelling::{ hunspell, symspell::LazySymSpell, LanguageSpeller };
+
+pub mod chain;
+pub mod dictd;
+pub mod free_dictionary;
+pub mod router;
+pub mod spellcheck;
+pub mod stardict;
+pub mod text;
+pub mod wiktionary;
+pub mod wordnet;
+
+/// The language that words are looked up in unless another one is configured.
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// Settings that apply to a single lookup.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LookupOptions {
+    /// Language code, such as `en` or `pt-BR`.
+    pub language: String,
+    /// The database to search, for providers that serve several.
+    pub database: Option<String>,
+}
+
+impl Default for LookupOptions {
+    fn default() -> Self {
+        LookupOptions {
+            language: DEFAULT_LANGUAGE.to_string(),
+            database: None,
+        }
+    }
+}
+
+/// A database that a provider can restrict its lookups to.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Database {
+    pub name: String,
+    pub description: String,
+}
+
+/// A source that words can be looked up from.
+#[async_trait]
+pub trait DictionaryProvider: Send + Sync {
+    /// A short name that identifies the provider.
+    fn name(&self) -> &str;
+
+    /// Looks up `word` and returns every entry found for it.
+    async fn lookup(&self, word: &str, options: &LookupOptions) -> Result<Vec<Thesaurus>, ApiError>;
+
+    /// Suggests a spelling for `word`. Providers without spellchecking return `None`.
+    async fn suggest(&self, _word: &str, _options: &LookupOptions) -> Result<Option<String>, ApiError> {
+        Ok(None)
+    }
+
+    /// Suggests spellings for `word`, the most likely first.
+    async fn suggestions(&self, word: &str, options: &LookupOptions) -> Result<Vec<String>, ApiError> {
+        Ok(self.suggest(word, options).await?.into_iter().collect())
+    }
+
+    /// Whether words are looked up without the network, such as from dictionary files.
+    /// Their results are not cached.
+    fn is_local(&self, _options: &LookupOptions) -> bool {
+        false
+    }
+
+    /// Lists the languages that words can be looked up in. Providers that ignore the language,
+    /// such as a set of dictionary files, return an empty list.
+    fn languages(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Lists the services whose API keys the provider uses, such as `serpapi`.
+    fn api_keys(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Lists the databases that lookups can be restricted to. Most providers have none.
+    async fn databases(&self) -> Result<Vec<Database>, ApiError> {
+        Ok(Vec::new())
+    }
+}
+
+/// A cheaply cloneable handle to a `DictionaryProvider`.
+#[derive(Clone)]
+pub struct Provider(Arc<dyn DictionaryProvider>);
+
+impl Provider {
+    pub fn new(provider: impl DictionaryProvider + 'static) -> Self {
+        Provider(Arc::new(provider))
+    }
+
+    /// Builds the provider selected in `config`, routing the languages listed under
+    /// `[languages]` to their own providers and correcting spellings offline where it can.
+    /// Providers that use web services send their requests through `http`.
+    pub fn from_config(config: &Config, http: &Http) -> Result<Provider, ApiError> {
+        let default = if !config.sources.is_empty() {
+            let sources = config.sources
+                .iter()
+                .map(|kind| Self::build(kind, config, http))
+                .collect::<Result<Vec<Provider>, ApiError>>()?;
+            Provider::new(chain::Chain::new(sources, config.merge))
+        } else if config.provider == ProviderKind::StarDict {
+            // Words missing from the dictionaries are looked up from the API.
+            let sources = vec![
+                Self::build(&ProviderKind::StarDict, config, http)?,
+                Self::build(&ProviderKind::FreeDictionary, config, http)?
+            ];
+            Provider::new(chain::Chain::new(sources, false))
+        } else {
+            Self::build(&config.provider, config, http)?
+        };
+        let provider = if config.languages.is_empty() {
+            default
+        } else {
+            let mut routes = Vec::new();
+            for (language, kind) in &config.languages {
+                routes.push((language.clone(), Self::build(kind, config, http)?));
+            }
+            Provider::new(router::LanguageRouter::new(default, routes))
+        };
+        let spellers = Self::spellers(config, &provider)?;
+        if spellers.is_empty() {
+            return Ok(provider);
+        }
+        Ok(Provider::new(spellcheck::SpellChecked::new(provider, spellers)))
+    }
+
+    /// Finds a Hunspell dictionary for every language that words can be looked up in,
+    /// followed by the `[spelling]` word list.
+    fn spellers(config: &Config, provider: &Provider) -> Result<Vec<LanguageSpeller>, ApiError> {
+        let mut languages = provider.languages();
+        languages.extend(config.language.clone());
+        languages.push(DEFAULT_LANGUAGE.to_string());
+        let mut spellers: Vec<LanguageSpeller> = Vec::new();
+        for language in languages {
+            if spellers.iter().any(|(l, _)| *l == language) {
+                continue;
+            }
+            let name = config.spelling.dictionaries.get(&language).map(String::as_str);
+            if let Some(path) = hunspell::find_dictionary(&config.spelling.hunspell_dir, &language, name) {
+                spellers.push((language, Box::new(hunspell::LazyHunspell::new(path))));
+            }
+        }
+        if let Some(word_list) = config.spelling.word_list.as_ref().filter(|path| !path.is_file()) {
+            return Err(ApiError::ConfigError(format!("Cannot find the word list {}", word_list.display())));
+        }
+        if let Some(word_list) = config.spelling.word_list() {
+            let symspell = LazySymSpell::new(word_list, config.spelling.max_distance);
+            spellers.push((config.spelling.language.clone(), Box::new(symspell)));
+        }
+        Ok(spellers)
+    }
+
+    fn build(kind: &ProviderKind, config: &Config, http: &Http) -> Result<Provider, ApiError> {
+        let provider = match kind {
+            ProviderKind::FreeDictionary =>
+                Provider::new(
+                    free_dictionary::FreeDictionary::new(&config.free_dictionary.base_url(), http.clone())
+                ),
+            ProviderKind::WordNet => Provider::new(wordnet::WordNet::open(config.wordnet.dir.clone())?),
+            ProviderKind::StarDict => Provider::new(stardict::StarDict::open(&config.stardict.dir)?),
+            ProviderKind::Dict =>
+                Provider::new(
+                    dictd::Dictd
+                        ::new(&config.dict.host, config.dict.port, &config.dict.database, &config.dict.strategy)
+                        .with_timeouts(
+                            Duration::from_secs(config.http.connect_timeout_secs),
+                            Duration::from_secs(config.http.timeout_secs)
+                        )
+                ),
+            ProviderKind::Wiktionary =>
+                Provider::new(wiktionary::Wiktionary::open(&config.wiktionary.store)?),
+        };
+        Ok(provider)
+    }
+}
+
+impl Default for Provider {
+    fn default() -> Self {
+        Provider::new(free_dictionary::FreeDictionary::default())
+    }
+}
+
+impl Deref for Provider {
+    type Target = dyn DictionaryProvider;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref()
+    }
+}
+
+impl fmt::Debug for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Provider").field(&self.name()).finish()
+    }
+}
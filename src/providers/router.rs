@@ -0,0 +1,79 @@
+use async_trait::async_trait;
+
+use crate::models::{ data::Thesaurus, errors::ApiError };
+use crate::providers::{ Database, DictionaryProvider, LookupOptions, Provider };
+
+/// Sends lookups in some languages to their own providers and everything else to a default one.
+#[derive(Clone, Debug)]
+pub struct LanguageRouter {
+    default: Provider,
+    routes: Vec<(String, Provider)>,
+}
+
+impl LanguageRouter {
+    pub fn new(default: Provider, routes: Vec<(String, Provider)>) -> Self {
+        LanguageRouter { default, routes }
+    }
+
+    fn route(&self, language: &str) -> &Provider {
+        self.routes
+            .iter()
+            .find(|(l, _)| l.eq_ignore_ascii_case(language))
+            .map_or(&self.default, |(_, provider)| provider)
+    }
+}
+
+#[async_trait]
+impl DictionaryProvider for LanguageRouter {
+    fn name(&self) -> &str {
+        self.default.name()
+    }
+
+    async fn lookup(&self, word: &str, options: &LookupOptions) -> Result<Vec<Thesaurus>, ApiError> {
+        self.route(&options.language).lookup(word, options).await
+    }
+
+    async fn suggest(&self, word: &str, options: &LookupOptions) -> Result<Option<String>, ApiError> {
+        self.route(&options.language).suggest(word, options).await
+    }
+
+    async fn suggestions(&self, word: &str, options: &LookupOptions) -> Result<Vec<String>, ApiError> {
+        self.route(&options.language).suggestions(word, options).await
+    }
+
+    fn is_local(&self, options: &LookupOptions) -> bool {
+        self.route(&options.language).is_local(options)
+    }
+
+    fn languages(&self) -> Vec<String> {
+        let mut languages = self.default.languages();
+        for (language, _) in &self.routes {
+            if !languages.contains(language) {
+                languages.push(language.clone());
+            }
+        }
+        languages
+    }
+
+    fn api_keys(&self) -> Vec<String> {
+        let mut keys = self.default.api_keys();
+        for key in self.routes.iter().flat_map(|(_, provider)| provider.api_keys()) {
+            if !keys.contains(&key) {
+                keys.push(key);
+            }
+        }
+        keys
+    }
+
+    /// Lists the databases of the default provider, or else of the first routed one that has any.
+    async fn databases(&self) -> Result<Vec<Database>, ApiError> {
+        let providers = std::iter::once(&self.default).chain(self.routes.iter().map(|(_, provider)| provider));
+        for provider in providers {
+            let databases = provider.databases().await?;
+            if !databases.is_empty() {
+                return Ok(databases);
+            }
+        }
+        Ok(Vec::new())
+    }
+}
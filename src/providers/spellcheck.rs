@@ -0,0 +1,158 @@
+use std::{ fmt, sync::Arc };
+
+use async_trait::async_trait;
+
+use crate::models::{ data::Thesaurus, errors::ApiError };
+use crate::providers::{ Database, DictionaryProvider, LookupOptions, Provider };
+use crate::spelling::{ LanguageSpeller, Speller };
+
+/// How many spellings are offered for a word that cannot be found.
+const SUGGESTION_LIMIT: usize = 5;
+
+/// Suggests spellings from local spellers before asking the provider it wraps.
+#[derive(Clone)]
+pub struct SpellChecked {
+    inner: Provider,
+    /// Spellers and the language each of them knows, in the order they are asked.
+    spellers: Vec<(String, Arc<dyn Speller>)>,
+}
+
+impl SpellChecked {
+    pub fn new(inner: Provider, spellers: Vec<LanguageSpeller>) -> Self {
+        let spellers = spellers
+            .into_iter()
+            .map(|(language, speller)| (language, Arc::from(speller)))
+            .collect();
+        SpellChecked { inner, spellers }
+    }
+
+    fn spellers_for<'a>(&'a self, options: &'a LookupOptions) -> impl Iterator<Item = &'a Arc<dyn Speller>> {
+        self.spellers
+            .iter()
+            .filter(|(language, _)| language.eq_ignore_ascii_case(&options.language))
+            .map(|(_, speller)| speller)
+    }
+}
+
+impl fmt::Debug for SpellChecked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let languages: Vec<&String> = self.spellers
+            .iter()
+            .map(|(language, _)| language)
+            .collect();
+        f.debug_struct("SpellChecked")
+            .field("inner", &self.inner)
+            .field("languages", &languages)
+            .finish()
+    }
+}
+
+#[async_trait]
+impl DictionaryProvider for SpellChecked {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn lookup(&self, word: &str, options: &LookupOptions) -> Result<Vec<Thesaurus>, ApiError> {
+        self.inner.lookup(word, options).await
+    }
+
+    async fn suggest(&self, word: &str, options: &LookupOptions) -> Result<Option<String>, ApiError> {
+        Ok(self.suggestions(word, options).await?.into_iter().next())
+    }
+
+    async fn suggestions(&self, word: &str, options: &LookupOptions) -> Result<Vec<String>, ApiError> {
+        if self.spellers_for(options).any(|s| s.check(word)) {
+            // Correctly spelled words that the dictionary lacks may be inflections of words it has.
+            let mut stems: Vec<String> = Vec::new();
+            for stem in self.spellers_for(options).flat_map(|s| s.stem(word)) {
+                if !stem.eq_ignore_ascii_case(word.trim()) && !stems.contains(&stem) {
+                    stems.push(stem);
+                }
+            }
+            if !stems.is_empty() {
+                return Ok(stems);
+            }
+        } else {
+            for speller in self.spellers_for(options) {
+                let suggestions = speller.suggest(word, SUGGESTION_LIMIT);
+                if !suggestions.is_empty() {
+                    return Ok(suggestions);
+                }
+            }
+        }
+        self.inner.suggestions(word, options).await
+    }
+
+    fn is_local(&self, options: &LookupOptions) -> bool {
+        self.inner.is_local(options)
+    }
+
+    fn languages(&self) -> Vec<String> {
+        self.inner.languages()
+    }
+
+    fn api_keys(&self) -> Vec<String> {
+        self.inner.api_keys()
+    }
+
+    async fn databases(&self) -> Result<Vec<Database>, ApiError> {
+        self.inner.databases().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spelling::{ hunspell::Hunspell, symspell::SymSpell };
+    use pretty_assertions::assert_eq;
+
+    /// A provider that finds nothing and suggests the same word for everything.
+    struct Remote;
+
+    #[async_trait]
+    impl DictionaryProvider for Remote {
+        fn name(&self) -> &str {
+            "remote"
+        }
+
+        async fn lookup(&self, _word: &str, _options: &LookupOptions) -> Result<Vec<Thesaurus>, ApiError> {
+            Err(ApiError::NotFound)
+        }
+
+        async fn suggest(&self, _word: &str, _options: &LookupOptions) -> Result<Option<String>, ApiError> {
+            Ok(Some(String::from("remote")))
+        }
+    }
+
+    fn spell_checked() -> SpellChecked {
+        let symspell = SymSpell::from_reader("happy 10\nharpy 2\n".as_bytes(), 2).unwrap();
+        let hunspell = Hunspell::from_bytes("SFX S Y 1\nSFX S 0 s .\n".as_bytes(), "1\ncat/S\n".as_bytes());
+        SpellChecked::new(
+            Provider::new(Remote),
+            vec![(String::from("en"), Box::new(hunspell)), (String::from("en"), Box::new(symspell))]
+        )
+    }
+
+    #[tokio::test]
+    async fn test_suggestions_from_speller() {
+        let suggestions = spell_checked().suggestions("hapy", &LookupOptions::default()).await.unwrap();
+        assert_eq!(vec![String::from("happy"), String::from("harpy")], suggestions);
+    }
+
+    #[tokio::test]
+    async fn test_suggestions_from_stems() {
+        let suggestions = spell_checked().suggestions("cats", &LookupOptions::default()).await.unwrap();
+        assert_eq!(vec![String::from("cat")], suggestions);
+    }
+
+    #[tokio::test]
+    async fn test_suggestions_fall_back_to_provider() {
+        let spell_checked = spell_checked();
+        let suggestions = spell_checked.suggestions("zzz", &LookupOptions::default()).await.unwrap();
+        assert_eq!(vec![String::from("remote")], suggestions);
+        let german = LookupOptions { language: String::from("de"), ..LookupOptions::default() };
+        let suggestions = spell_checked.suggestions("hapy", &german).await.unwrap();
+        assert_eq!(vec![String::from("remote")], suggestions);
+    }
+}
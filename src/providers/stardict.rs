@@ -0,0 +1,484 @@
+use std::{
+    collections::HashMap,
+    fs::{ self, File },
+    io::{ self, BufRead, BufReader, Read, Seek, SeekFrom },
+    path::{ Path, PathBuf },
+};
+
+use async_trait::async_trait;
+use flate2::{ read::GzDecoder, Decompress, FlushDecompress };
+
+use crate::models::{ data::Thesaurus, errors::ApiError };
+use crate::providers::{ text::parse_text, DictionaryProvider, LookupOptions };
+
+/// How deep to look for `.ifo` files below the configured directory.
+const MAX_SCAN_DEPTH: usize = 3;
+
+/// Looks words up from the StarDict dictionaries found in a directory.
+#[derive(Debug)]
+pub struct StarDict {
+    dictionaries: Vec<Dictionary>,
+}
+
+impl StarDict {
+    /// Loads the index of every dictionary found in `dir` and its subdirectories.
+    pub fn open(dir: &Path) -> io::Result<StarDict> {
+        let mut ifo_files = Vec::new();
+        find_ifo_files(dir, MAX_SCAN_DEPTH, &mut ifo_files)?;
+        if ifo_files.is_empty() {
+            return Err(
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("no StarDict dictionaries found in {}", dir.display())
+                )
+            );
+        }
+        ifo_files.sort();
+        let dictionaries = ifo_files
+            .iter()
+            .map(|ifo| Dictionary::open(ifo))
+            .collect::<io::Result<Vec<Dictionary>>>()?;
+        Ok(StarDict { dictionaries })
+    }
+}
+
+#[async_trait]
+impl DictionaryProvider for StarDict {
+    fn name(&self) -> &str {
+        "stardict"
+    }
+
+    fn is_local(&self, _options: &LookupOptions) -> bool {
+        true
+    }
+
+    async fn lookup(&self, word: &str, _options: &LookupOptions) -> Result<Vec<Thesaurus>, ApiError> {
+        let key = word.trim().to_lowercase();
+        let mut meanings = Vec::new();
+        for dictionary in &self.dictionaries {
+            for text in dictionary.entries(&key)? {
+                meanings.extend(parse_text(&text, &dictionary.name));
+            }
+        }
+        if meanings.is_empty() {
+            return Err(ApiError::NotFound);
+        }
+        Ok(
+            vec![Thesaurus {
+                word: Some(word.trim().to_string()),
+                meanings: Some(meanings),
+                ..Thesaurus::default()
+            }]
+        )
+    }
+}
+
+/// A single dictionary, made up of an `.ifo`, an `.idx` and a `.dict` file.
+#[derive(Debug)]
+struct Dictionary {
+    name: String,
+    same_type_sequence: Option<String>,
+    /// Headwords in lower case, mapped to the location of their articles in the `.dict` file.
+    index: HashMap<String, Vec<(u64, usize)>>,
+    data: DictData,
+}
+
+impl Dictionary {
+    fn open(ifo: &Path) -> io::Result<Dictionary> {
+        let info = read_ifo(ifo)?;
+        let offset_bits: u32 = info
+            .get("idxoffsetbits")
+            .and_then(|bits| bits.parse().ok())
+            .unwrap_or(32);
+
+        let idx = ifo.with_extension("idx");
+        let idx_gz = ifo.with_extension("idx.gz");
+        let idx_bytes = if idx.is_file() {
+            fs::read(&idx)?
+        } else {
+            let mut bytes = Vec::new();
+            GzDecoder::new(File::open(&idx_gz)?).read_to_end(&mut bytes)?;
+            bytes
+        };
+
+        let dict = ifo.with_extension("dict");
+        let data = if dict.is_file() {
+            DictData::Plain(dict)
+        } else {
+            DictData::DictZip(DictZip::open(&ifo.with_extension("dict.dz"))?)
+        };
+
+        Ok(Dictionary {
+            name: info
+                .get("bookname")
+                .cloned()
+                .unwrap_or_else(|| ifo.file_stem().unwrap_or_default().to_string_lossy().to_string()),
+            same_type_sequence: info.get("sametypesequence").cloned(),
+            index: parse_idx(&idx_bytes, offset_bits)?,
+            data,
+        })
+    }
+
+    /// Returns the text of every article stored under `key`.
+    fn entries(&self, key: &str) -> io::Result<Vec<String>> {
+        let mut entries = Vec::new();
+        for &(offset, size) in self.index.get(key).into_iter().flatten() {
+            let article = self.data.read(offset, size)?;
+            let text: Vec<String> = parse_fields(&article, self.same_type_sequence.as_deref())
+                .into_iter()
+                .filter_map(|(field_type, value)| text_of(field_type, &value))
+                .collect();
+            entries.push(text.join("\n"));
+        }
+        Ok(entries)
+    }
+}
+
+/// The `.dict` file, either as is or compressed with dictzip.
+#[derive(Debug)]
+enum DictData {
+    Plain(PathBuf),
+    DictZip(DictZip),
+}
+
+impl DictData {
+    fn read(&self, offset: u64, size: usize) -> io::Result<Vec<u8>> {
+        match self {
+            DictData::Plain(path) => {
+                let mut file = File::open(path)?;
+                file.seek(SeekFrom::Start(offset))?;
+                let mut buf = vec![0; size];
+                file.read_exact(&mut buf)?;
+                Ok(buf)
+            }
+            DictData::DictZip(dz) => dz.read(offset, size),
+        }
+    }
+}
+
+/// A gzip file whose deflate stream is split into chunks that can be inflated on their own.
+#[derive(Debug)]
+struct DictZip {
+    path: PathBuf,
+    chunk_len: usize,
+    /// Offset and compressed size of each chunk.
+    chunks: Vec<(u64, usize)>,
+}
+
+impl DictZip {
+    const FHCRC: u8 = 0x02;
+    const FEXTRA: u8 = 0x04;
+    const FNAME: u8 = 0x08;
+    const FCOMMENT: u8 = 0x10;
+
+    fn open(path: &Path) -> io::Result<DictZip> {
+        let invalid = |msg: &str| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), msg))
+        };
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut header = [0u8; 10];
+        reader.read_exact(&mut header)?;
+        if header[..3] != [0x1f, 0x8b, 0x08] {
+            return Err(invalid("not a gzip file"));
+        }
+        let flags = header[3];
+        if flags & Self::FEXTRA == 0 {
+            return Err(invalid("not a dictzip file"));
+        }
+
+        let mut xlen = [0u8; 2];
+        reader.read_exact(&mut xlen)?;
+        let mut extra = vec![0; u16::from_le_bytes(xlen) as usize];
+        reader.read_exact(&mut extra)?;
+        let (chunk_len, sizes) = parse_random_access_field(&extra).ok_or_else(||
+            invalid("missing dictzip chunk table")
+        )?;
+        if chunk_len == 0 {
+            return Err(invalid("dictzip chunk length is zero"));
+        }
+
+        for flag in [Self::FNAME, Self::FCOMMENT] {
+            if flags & flag != 0 {
+                reader.read_until(0, &mut Vec::new())?;
+            }
+        }
+        if flags & Self::FHCRC != 0 {
+            reader.read_exact(&mut [0u8; 2])?;
+        }
+
+        let mut offset = reader.stream_position()?;
+        let chunks = sizes
+            .into_iter()
+            .map(|size| {
+                let chunk = (offset, size);
+                offset += size as u64;
+                chunk
+            })
+            .collect();
+        Ok(DictZip { path: path.to_path_buf(), chunk_len, chunks })
+    }
+
+    fn read(&self, offset: u64, size: usize) -> io::Result<Vec<u8>> {
+        if size == 0 {
+            return Ok(Vec::new());
+        }
+        let first = (offset as usize) / self.chunk_len;
+        let last = (offset as usize + size - 1) / self.chunk_len;
+        let mut file = File::open(&self.path)?;
+        let mut inflated = Vec::with_capacity((last - first + 1) * self.chunk_len);
+        for &(chunk_offset, chunk_size) in self.chunks.get(first..=last).ok_or_else(||
+            io::Error::new(io::ErrorKind::UnexpectedEof, "article is past the end of the dictionary")
+        )? {
+            let mut compressed = vec![0; chunk_size];
+            file.seek(SeekFrom::Start(chunk_offset))?;
+            file.read_exact(&mut compressed)?;
+            let mut chunk = Vec::with_capacity(self.chunk_len);
+            Decompress::new(false)
+                .decompress_vec(&compressed, &mut chunk, FlushDecompress::Sync)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            inflated.extend(chunk);
+        }
+        let start = (offset as usize) - first * self.chunk_len;
+        inflated
+            .get(start..start + size)
+            .map(|article| article.to_vec())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "truncated dictzip chunk"))
+    }
+}
+
+/// Reads the `RA` subfield of a gzip header, which holds the dictzip chunk length and sizes.
+fn parse_random_access_field(extra: &[u8]) -> Option<(usize, Vec<usize>)> {
+    let mut pos = 0;
+    while pos + 4 <= extra.len() {
+        let len = u16::from_le_bytes([extra[pos + 2], extra[pos + 3]]) as usize;
+        let data = extra.get(pos + 4..pos + 4 + len)?;
+        if &extra[pos..pos + 2] == b"RA" {
+            let read_u16 = |i: usize| -> Option<usize> {
+                Some(u16::from_le_bytes([*data.get(i)?, *data.get(i + 1)?]) as usize)
+            };
+            let chunk_len = read_u16(2)?;
+            let chunk_count = read_u16(4)?;
+            let sizes = (0..chunk_count).map(|i| read_u16(6 + i * 2)).collect::<Option<Vec<usize>>>()?;
+            return Some((chunk_len, sizes));
+        }
+        pos += 4 + len;
+    }
+    None
+}
+
+fn find_ifo_files(dir: &Path, depth: usize, found: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() && depth > 0 {
+            find_ifo_files(&path, depth - 1, found)?;
+        } else if path.extension().is_some_and(|ext| ext == "ifo") {
+            found.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn read_ifo(path: &Path) -> io::Result<HashMap<String, String>> {
+    let contents = fs::read_to_string(path)?;
+    Ok(
+        contents
+            .lines()
+            .skip(1)
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .collect()
+    )
+}
+
+/// Parses an `.idx` file: a NUL-terminated headword followed by a big-endian offset and size.
+fn parse_idx(bytes: &[u8], offset_bits: u32) -> io::Result<HashMap<String, Vec<(u64, usize)>>> {
+    let offset_len = if offset_bits == 64 { 8 } else { 4 };
+    let mut index: HashMap<String, Vec<(u64, usize)>> = HashMap::new();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let end = bytes[pos..]
+            .iter()
+            .position(|&b| b == 0)
+            .map(|i| pos + i)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "truncated .idx file"))?;
+        let word = String::from_utf8_lossy(&bytes[pos..end]).to_lowercase();
+        let fields = bytes
+            .get(end + 1..end + 1 + offset_len + 4)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "truncated .idx file"))?;
+        let offset = fields[..offset_len].iter().fold(0u64, |acc, &b| (acc << 8) | (b as u64));
+        let size = u32::from_be_bytes([
+            fields[offset_len],
+            fields[offset_len + 1],
+            fields[offset_len + 2],
+            fields[offset_len + 3],
+        ]) as usize;
+        index.entry(word).or_default().push((offset, size));
+        pos = end + 1 + offset_len + 4;
+    }
+    Ok(index)
+}
+
+/// Splits an article into its typed fields. Lower-case types are text, upper-case types are binary.
+fn parse_fields(article: &[u8], same_type_sequence: Option<&str>) -> Vec<(char, Vec<u8>)> {
+    let mut fields = Vec::new();
+    let mut pos = 0;
+    let types: Vec<char> = same_type_sequence.unwrap_or("").chars().collect();
+    let mut type_idx = 0;
+    while pos < article.len() {
+        let (field_type, is_last) = if types.is_empty() {
+            let field_type = article[pos] as char;
+            pos += 1;
+            (field_type, false)
+        } else if type_idx < types.len() {
+            type_idx += 1;
+            (types[type_idx - 1], type_idx == types.len())
+        } else {
+            break;
+        };
+        let end = if is_last {
+            article.len()
+        } else if field_type.is_ascii_lowercase() {
+            article[pos..]
+                .iter()
+                .position(|&b| b == 0)
+                .map_or(article.len(), |i| pos + i)
+        } else {
+            let size = article
+                .get(pos..pos + 4)
+                .map_or(0, |b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize);
+            pos += 4;
+            (pos + size).min(article.len())
+        };
+        fields.push((field_type, article[pos.min(end)..end].to_vec()));
+        // Skip the NUL terminator of text fields.
+        pos = if field_type.is_ascii_lowercase() && !is_last { end + 1 } else { end };
+    }
+    fields
+}
+
+/// Converts a text field into plain text, or `None` for fields that cannot be shown.
+fn text_of(field_type: char, value: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(value);
+    match field_type {
+        'm' | 'l' | 'y' => Some(text.to_string()),
+        'g' | 'h' | 'x' | 'k' | 'w' => Some(strip_markup(&text)),
+        _ => None,
+    }
+}
+
+/// Removes the tags from Pango, HTML and XDXF markup, keeping line breaks.
+fn strip_markup(markup: &str) -> String {
+    let mut text = String::with_capacity(markup.len());
+    let mut tag = String::new();
+    let mut in_tag = false;
+    for c in markup.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                tag.clear();
+            }
+            '>' if in_tag => {
+                in_tag = false;
+                let name = tag.trim_start_matches('/').trim_end_matches('/').trim();
+                let name = name.split_whitespace().next().unwrap_or("").to_lowercase();
+                if ["br", "p", "div", "li", "def", "blockquote"].contains(&name.as_str()) {
+                    text.push('\n');
+                }
+            }
+            _ if in_tag => tag.push(c),
+            _ => text.push(c),
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::{ Compress, Compression, FlushCompress };
+    use pretty_assertions::assert_eq;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("thesaurust-{}-{}", std::process::id(), name))
+    }
+
+    /// Compresses `data` the way `dictzip` does, flushing the deflate stream after every chunk.
+    fn write_dictzip(path: &Path, data: &[u8], chunk_len: usize) {
+        let mut compressor = Compress::new(Compression::default(), false);
+        let mut sizes = Vec::new();
+        let mut body = Vec::new();
+        for (i, chunk) in data.chunks(chunk_len).enumerate() {
+            let is_last = (i + 1) * chunk_len >= data.len();
+            let flush = if is_last { FlushCompress::Finish } else { FlushCompress::Full };
+            let mut out = Vec::with_capacity(chunk_len * 2 + 64);
+            compressor.compress_vec(chunk, &mut out, flush).unwrap();
+            sizes.push(out.len() as u16);
+            body.extend(out);
+        }
+        let mut extra = b"RA".to_vec();
+        extra.extend(((6 + sizes.len() * 2) as u16).to_le_bytes());
+        extra.extend(1u16.to_le_bytes());
+        extra.extend((chunk_len as u16).to_le_bytes());
+        extra.extend((sizes.len() as u16).to_le_bytes());
+        sizes.iter().for_each(|s| extra.extend(s.to_le_bytes()));
+
+        let mut file = vec![0x1f, 0x8b, 0x08, DictZip::FEXTRA, 0, 0, 0, 0, 0, 3];
+        file.extend((extra.len() as u16).to_le_bytes());
+        file.extend(extra);
+        file.extend(body);
+        fs::write(path, file).unwrap();
+    }
+
+    #[test]
+    fn test_dictzip_reads_across_chunks() {
+        let path = temp_path("test.dict.dz");
+        let data: Vec<u8> = (0..200u8).collect();
+        write_dictzip(&path, &data, 16);
+        let dz = DictZip::open(&path).unwrap();
+        assert_eq!(13, dz.chunks.len());
+        assert_eq!(data[10..50].to_vec(), dz.read(10, 40).unwrap());
+        assert_eq!(data[190..].to_vec(), dz.read(190, 10).unwrap());
+        assert!(dz.read(500, 0).unwrap().is_empty());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_dictzip_without_chunk_length_is_invalid() {
+        let path = temp_path("zero.dict.dz");
+        write_dictzip(&path, b"hello", 0x10000);
+        let err = DictZip::open(&path).unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_parse_idx() {
+        let mut bytes = b"apple\0".to_vec();
+        bytes.extend(0u32.to_be_bytes());
+        bytes.extend(12u32.to_be_bytes());
+        bytes.extend(b"Apple\0");
+        bytes.extend(12u32.to_be_bytes());
+        bytes.extend(7u32.to_be_bytes());
+        let index = parse_idx(&bytes, 32).unwrap();
+        assert_eq!(Some(&vec![(0, 12), (12, 7)]), index.get("apple"));
+    }
+
+    #[test]
+    fn test_parse_fields() {
+        assert_eq!(vec![('m', b"a fruit".to_vec())], parse_fields(b"a fruit", Some("m")));
+        assert_eq!(
+            vec![('t', b"ap".to_vec()), ('m', b"a fruit".to_vec())],
+            parse_fields(b"tap\0ma fruit\0", None)
+        );
+    }
+
+    #[test]
+    fn test_strip_markup() {
+        assert_eq!("a <b>\nfruit", strip_markup("<i>a</i> &lt;b&gt;<br/>fruit"));
+    }
+}
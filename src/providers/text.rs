@@ -0,0 +1,202 @@
+//! Turns the plain-text articles served by StarDict dictionaries and dictd into `Meaning`s.
+
+use crate::models::data::{ Definition, Meaning };
+
+/// Maps the abbreviations that dictionaries use for parts of speech onto their full names.
+fn part_of_speech(marker: &str) -> Option<&'static str> {
+    let marker = marker.trim_end_matches('.').to_lowercase();
+    let part_of_speech = match marker.as_str() {
+        "n" | "noun" => "noun",
+        "v" | "vt" | "vi" | "verb" => "verb",
+        "adj" | "adjective" => "adjective",
+        "adv" | "adverb" => "adverb",
+        "pron" | "pronoun" => "pronoun",
+        "prep" | "preposition" => "preposition",
+        "conj" | "conjunction" => "conjunction",
+        "int" | "interj" | "interjection" => "interjection",
+        _ => {
+            return None;
+        }
+    };
+    Some(part_of_speech)
+}
+
+/// Strips list markers such as `1.`, `2)`, `3:` or `(4)` from the start of a line.
+fn strip_numbering(line: &str) -> &str {
+    let trimmed = line.trim_start_matches('(');
+    let digits = trimmed
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .count();
+    if digits > 0 {
+        if let Some(rest) = trimmed[digits..].strip_prefix(['.', ')', ':']) {
+            return rest.trim_start();
+        }
+    }
+    line
+}
+
+fn is_example(line: &str) -> bool {
+    line.starts_with('"') || line.starts_with("e.g.") || line.starts_with("Ex:")
+}
+
+/// Splits a gloss into its definition and the quoted examples that follow it, as in
+/// `a domesticated canid; "the dog barked all night"`.
+pub fn split_gloss(gloss: &str) -> (String, Vec<String>) {
+    let (examples, definition): (Vec<&str>, Vec<&str>) = gloss
+        .split("; ")
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .partition(|p| p.starts_with('"'));
+    let examples = examples
+        .into_iter()
+        .map(|p| p.trim_matches('"').to_string())
+        .collect();
+    (definition.join("; "), examples)
+}
+
+/// Removes a `[syn: {a}, {b}]` style cross-reference from `text`, returning the words it listed.
+fn take_references(text: &str, label: &str) -> (String, Vec<String>) {
+    let marker = format!("[{}:", label);
+    let start = match text.find(&marker) {
+        Some(start) => start,
+        None => {
+            return (text.to_string(), Vec::new());
+        }
+    };
+    let end = text[start..].find(']').map_or(text.len(), |i| start + i + 1);
+    let words = text[start + marker.len()..end]
+        .trim_end_matches(']')
+        .split(',')
+        .map(|w| w.trim().trim_matches(|c| c == '{' || c == '}').to_string())
+        .filter(|w| !w.is_empty())
+        .collect();
+    (format!("{} {}", text[..start].trim_end(), text[end..].trim_start()), words)
+}
+
+fn definition_from(text: &str) -> Definition {
+    let (text, synonyms) = take_references(text, "syn");
+    let (text, antonyms) = take_references(&text, "ant");
+    let (definition, examples) = split_gloss(&text);
+    Definition {
+        definition: Some(definition.replace(['{', '}'], "")),
+        example: examples.into_iter().next(),
+        synonyms: Some(synonyms).filter(|s| !s.is_empty()),
+        antonyms: Some(antonyms).filter(|a| !a.is_empty()),
+        source: None,
+    }
+}
+
+/// Groups the lines of a plain-text article into meanings, one per part of speech marker.
+/// Lines indented deeper than the line before them continue the same definition.
+/// Articles without markers end up in a single meaning labelled `fallback`.
+pub fn parse_text(text: &str, fallback: &str) -> Vec<Meaning> {
+    let mut meanings: Vec<Meaning> = Vec::new();
+    let mut current: Option<String> = None;
+    let mut definitions: Vec<Definition> = Vec::new();
+    // The definition being read and the indentation of its first line.
+    let mut lines: Vec<String> = Vec::new();
+    let mut indent = 0;
+
+    let finish = |lines: &mut Vec<String>, definitions: &mut Vec<Definition>| {
+        if !lines.is_empty() {
+            definitions.push(definition_from(&lines.join(" ")));
+            lines.clear();
+        }
+    };
+    let mut flush = |current: &Option<String>, definitions: &mut Vec<Definition>| {
+        if !definitions.is_empty() {
+            meanings.push(Meaning {
+                partOfSpeech: Some(current.clone().unwrap_or(fallback.to_string())),
+                definitions: Some(std::mem::take(definitions)),
+                ..Meaning::default()
+            });
+        }
+    };
+
+    for raw in text.lines() {
+        let line = raw.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let line_indent = raw.len() - raw.trim_start().len();
+        let (first, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        if let Some(pos) = part_of_speech(first) {
+            finish(&mut lines, &mut definitions);
+            flush(&current, &mut definitions);
+            current = Some(pos.to_string());
+            let rest = strip_numbering(rest.trim());
+            if !rest.is_empty() {
+                lines.push(rest.to_string());
+                indent = line_indent;
+            }
+        } else if is_example(line) && !lines.is_empty() {
+            let example = line.trim_start_matches("e.g.").trim_start_matches("Ex:").trim();
+            lines.push(format!("; \"{}\"", example.trim_matches('"')));
+        } else if !lines.is_empty() && line_indent > indent && strip_numbering(line) == line {
+            lines.push(line.to_string());
+        } else {
+            finish(&mut lines, &mut definitions);
+            lines.push(strip_numbering(line).to_string());
+            indent = line_indent;
+        }
+    }
+    finish(&mut lines, &mut definitions);
+    flush(&current, &mut definitions);
+    meanings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_parse_text() {
+        let text =
+            "n. 1. the fruit of the apple tree\n\"an apple a day\"\n2. the tree itself\nadj. apple-coloured";
+        let meanings = parse_text(text, "Sample");
+        assert_eq!(2, meanings.len());
+        assert_eq!(Some(String::from("noun")), meanings[0].partOfSpeech);
+        let definitions = meanings[0].definitions.clone().unwrap();
+        assert_eq!(Some(String::from("the fruit of the apple tree")), definitions[0].definition);
+        assert_eq!(Some(String::from("an apple a day")), definitions[0].example);
+        assert_eq!(Some(String::from("the tree itself")), definitions[1].definition);
+        assert_eq!(Some(String::from("adjective")), meanings[1].partOfSpeech);
+    }
+
+    #[test]
+    fn test_parse_text_without_markers() {
+        let meanings = parse_text("a round fruit", "Sample");
+        assert_eq!(Some(String::from("Sample")), meanings[0].partOfSpeech);
+    }
+
+    #[test]
+    fn test_parse_text_in_dictd_wordnet_format() {
+        let text =
+            "    n 1: a member of the genus Canis; \"the dog barked all\n         night\" [syn: {dog}, {domestic dog},\n         {Canis familiaris}]\n    2: informal term for a man [syn: {chap}, {fellow}]\n    v 1: go after with the intent to catch [syn: {chase},\n         {tail}] [ant: {lead}]";
+        let meanings = parse_text(text, "wn");
+        assert_eq!(2, meanings.len());
+        let nouns = meanings[0].definitions.clone().unwrap();
+        assert_eq!(2, nouns.len());
+        assert_eq!(Some(String::from("a member of the genus Canis")), nouns[0].definition);
+        assert_eq!(Some(String::from("the dog barked all night")), nouns[0].example);
+        assert_eq!(
+            Some(vec![String::from("dog"), String::from("domestic dog"), String::from("Canis familiaris")]),
+            nouns[0].synonyms
+        );
+        let verbs = meanings[1].definitions.clone().unwrap();
+        assert_eq!(Some(String::from("verb")), meanings[1].partOfSpeech);
+        assert_eq!(Some(vec![String::from("chase"), String::from("tail")]), verbs[0].synonyms);
+        assert_eq!(Some(vec![String::from("lead")]), verbs[0].antonyms);
+    }
+
+    #[test]
+    fn test_split_gloss() {
+        let (definition, examples) = split_gloss(
+            "having desirable or positive qualities; \"good news from the hospital\"; \"a good report card\""
+        );
+        assert_eq!("having desirable or positive qualities", definition);
+        assert_eq!(vec!["good news from the hospital", "a good report card"], examples);
+    }
+}
@@ -0,0 +1,401 @@
+use std::{
+    collections::HashMap,
+    fs::{ self, File },
+    io::{ self, BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write },
+    path::{ Path, PathBuf },
+};
+
+use async_trait::async_trait;
+use serde_derive::{ Deserialize, Serialize };
+
+use crate::models::{ data::{ Definition, License, Meaning, Phonetic, Thesaurus }, errors::ApiError };
+use crate::providers::{ DictionaryProvider, LookupOptions };
+
+const ENTRIES_FILE: &str = "entries.jsonl";
+const INDEX_FILE: &str = "index.tsv";
+
+/// Wiktionary text is available under CC BY-SA.
+const LICENSE_NAME: &str = "CC BY-SA 4.0";
+const LICENSE_URL: &str = "https://creativecommons.org/licenses/by-sa/4.0";
+
+/// An entry of a kaikki.org extract. Only the fields that the store keeps are read.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct KaikkiEntry {
+    word: String,
+    lang_code: String,
+    pos: String,
+    etymology_text: Option<String>,
+    sounds: Vec<KaikkiSound>,
+    senses: Vec<KaikkiSense>,
+    synonyms: Vec<KaikkiLink>,
+    antonyms: Vec<KaikkiLink>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct KaikkiSound {
+    ipa: Option<String>,
+    mp3_url: Option<String>,
+    ogg_url: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct KaikkiSense {
+    glosses: Vec<String>,
+    examples: Vec<KaikkiExample>,
+    synonyms: Vec<KaikkiLink>,
+    antonyms: Vec<KaikkiLink>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct KaikkiExample {
+    text: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct KaikkiLink {
+    word: String,
+}
+
+fn words_of(links: Vec<KaikkiLink>) -> Vec<String> {
+    links
+        .into_iter()
+        .map(|l| l.word)
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// An entry as kept in the store: one word in one language with one part of speech.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct StoredEntry {
+    pub word: String,
+    pub lang_code: String,
+    pub pos: String,
+    pub etymology: Option<String>,
+    pub pronunciations: Vec<Pronunciation>,
+    pub senses: Vec<StoredSense>,
+    pub synonyms: Vec<String>,
+    pub antonyms: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct Pronunciation {
+    pub ipa: Option<String>,
+    pub audio: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct StoredSense {
+    pub gloss: String,
+    pub examples: Vec<String>,
+    pub synonyms: Vec<String>,
+    pub antonyms: Vec<String>,
+}
+
+impl From<KaikkiEntry> for StoredEntry {
+    fn from(entry: KaikkiEntry) -> Self {
+        StoredEntry {
+            word: entry.word,
+            lang_code: entry.lang_code,
+            pos: entry.pos,
+            etymology: entry.etymology_text.filter(|e| !e.is_empty()),
+            pronunciations: entry.sounds
+                .into_iter()
+                .filter(|s| s.ipa.is_some() || s.mp3_url.is_some() || s.ogg_url.is_some())
+                .map(|s| Pronunciation { ipa: s.ipa, audio: s.mp3_url.or(s.ogg_url) })
+                .collect(),
+            senses: entry.senses
+                .into_iter()
+                .filter(|s| !s.glosses.is_empty())
+                .map(|s| StoredSense {
+                    gloss: s.glosses.join(": "),
+                    examples: s.examples
+                        .into_iter()
+                        .map(|e| e.text)
+                        .filter(|e| !e.is_empty())
+                        .collect(),
+                    synonyms: words_of(s.synonyms),
+                    antonyms: words_of(s.antonyms),
+                })
+                .collect(),
+            synonyms: words_of(entry.synonyms),
+            antonyms: words_of(entry.antonyms),
+        }
+    }
+}
+
+impl StoredEntry {
+    fn to_meaning(&self) -> Meaning {
+        let part_of_speech = match self.pos.as_str() {
+            "adj" => "adjective",
+            "adv" => "adverb",
+            "prep" => "preposition",
+            "conj" => "conjunction",
+            "intj" => "interjection",
+            pos => pos,
+        };
+        Meaning {
+            partOfSpeech: Some(part_of_speech.to_string()),
+            definitions: Some(
+                self.senses
+                    .iter()
+                    .map(|s| Definition {
+                        definition: Some(s.gloss.clone()),
+                        example: s.examples.first().cloned(),
+                        synonyms: Some(s.synonyms.clone()).filter(|s| !s.is_empty()),
+                        antonyms: Some(s.antonyms.clone()).filter(|a| !a.is_empty()),
+                        source: None,
+                    })
+                    .collect()
+            ),
+            synonyms: Some(self.synonyms.clone()).filter(|s| !s.is_empty()),
+            antonyms: Some(self.antonyms.clone()).filter(|a| !a.is_empty()),
+        }
+    }
+
+    /// Describes the word without its meanings, crediting the Wiktionary page it came from.
+    fn to_thesaurus(&self) -> Thesaurus {
+        let phonetics: Vec<Phonetic> = self.pronunciations
+            .iter()
+            .map(|p| Phonetic {
+                text: p.ipa.clone(),
+                audio: p.audio.clone(),
+                ..Phonetic::default()
+            })
+            .collect();
+        Thesaurus {
+            word: Some(self.word.clone()),
+            phonetic: self.pronunciations.iter().find_map(|p| p.ipa.clone()),
+            phonetics: Some(phonetics).filter(|p| !p.is_empty()),
+            origin: self.etymology.clone(),
+            meanings: None,
+            sourceUrls: Some(
+                vec![format!("https://en.wiktionary.org/wiki/{}", self.word.replace(' ', "_"))]
+            ),
+            license: Some(License {
+                name: Some(LICENSE_NAME.to_string()),
+                url: Some(LICENSE_URL.to_string()),
+            }),
+        }
+    }
+}
+
+/// What an import did.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImportSummary {
+    pub entries: usize,
+    pub words: usize,
+    pub skipped: usize,
+}
+
+/// Reads a kaikki.org JSONL extract and replaces the store in `store` with its entries.
+/// Lines that cannot be parsed are skipped and counted.
+pub fn import(input: impl Read, store: &Path) -> io::Result<ImportSummary> {
+    fs::create_dir_all(store)?;
+    let entries_tmp = store.join(format!("{}.tmp", ENTRIES_FILE));
+    let index_tmp = store.join(format!("{}.tmp", INDEX_FILE));
+
+    let mut summary = ImportSummary::default();
+    let mut index: HashMap<String, Vec<Location>> = HashMap::new();
+    let mut writer = BufWriter::new(File::create(&entries_tmp)?);
+    let mut offset = 0;
+    for line in BufReader::new(input).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: StoredEntry = match serde_json::from_str::<KaikkiEntry>(&line) {
+            Ok(entry) if !entry.word.is_empty() => entry.into(),
+            _ => {
+                summary.skipped += 1;
+                continue;
+            }
+        };
+        let mut record = serde_json::to_string(&entry)?;
+        record.push('\n');
+        writer.write_all(record.as_bytes())?;
+        index.entry(entry.word.to_lowercase()).or_default().push(Location {
+            lang_code: entry.lang_code.clone(),
+            offset,
+            len: record.len(),
+        });
+        offset += record.len() as u64;
+        summary.entries += 1;
+    }
+    writer.flush()?;
+
+    let mut words: Vec<(&String, &Vec<Location>)> = index.iter().collect();
+    words.sort_by(|a, b| a.0.cmp(b.0));
+    let mut writer = BufWriter::new(File::create(&index_tmp)?);
+    for (word, locations) in &words {
+        for location in locations.iter() {
+            writeln!(writer, "{}\t{}\t{}\t{}", word, location.lang_code, location.offset, location.len)?;
+        }
+    }
+    writer.flush()?;
+    summary.words = words.len();
+
+    fs::rename(entries_tmp, store.join(ENTRIES_FILE))?;
+    fs::rename(index_tmp, store.join(INDEX_FILE))?;
+    Ok(summary)
+}
+
+/// Where an entry is kept in the entries file.
+#[derive(Clone, Debug, PartialEq)]
+struct Location {
+    lang_code: String,
+    offset: u64,
+    len: usize,
+}
+
+/// Looks words up from a store built by `import`.
+#[derive(Debug)]
+pub struct Wiktionary {
+    entries: PathBuf,
+    /// Words in lower case, mapped to the location of their entries.
+    index: HashMap<String, Vec<Location>>,
+    languages: Vec<String>,
+}
+
+impl Wiktionary {
+    pub fn open(store: &Path) -> io::Result<Wiktionary> {
+        let index_path = store.join(INDEX_FILE);
+        let file = File::open(&index_path).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("{}: {} (run `thesaurust import-wiktionary` first)", index_path.display(), e)
+            )
+        })?;
+        let mut index: HashMap<String, Vec<Location>> = HashMap::new();
+        let mut languages: Vec<String> = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            let mut fields = line.rsplitn(4, '\t');
+            let (len, offset, lang_code, word) = (fields.next(), fields.next(), fields.next(), fields.next());
+            if let (Some(len), Some(offset), Some(lang_code), Some(word)) = (len, offset, lang_code, word) {
+                if let (Ok(offset), Ok(len)) = (offset.parse(), len.parse()) {
+                    if !languages.iter().any(|l| l == lang_code) {
+                        languages.push(lang_code.to_string());
+                    }
+                    index.entry(word.to_string()).or_default().push(Location {
+                        lang_code: lang_code.to_string(),
+                        offset,
+                        len,
+                    });
+                }
+            }
+        }
+        languages.sort();
+        Ok(Wiktionary { entries: store.join(ENTRIES_FILE), index, languages })
+    }
+
+    /// Returns every stored entry for `word` in the language `lang_code`.
+    pub fn entries(&self, word: &str, lang_code: &str) -> io::Result<Vec<StoredEntry>> {
+        let locations = match self.index.get(&word.trim().to_lowercase()) {
+            Some(locations) => locations,
+            None => {
+                return Ok(Vec::new());
+            }
+        };
+        let mut file = File::open(&self.entries)?;
+        let mut entries = Vec::with_capacity(locations.len());
+        for location in locations.iter().filter(|l| l.lang_code.eq_ignore_ascii_case(lang_code)) {
+            let mut record = vec![0; location.len];
+            file.seek(SeekFrom::Start(location.offset))?;
+            file.read_exact(&mut record)?;
+            entries.push(serde_json::from_slice(&record)?);
+        }
+        Ok(entries)
+    }
+}
+
+#[async_trait]
+impl DictionaryProvider for Wiktionary {
+    fn name(&self) -> &str {
+        "wiktionary"
+    }
+
+    fn is_local(&self, _options: &LookupOptions) -> bool {
+        true
+    }
+
+    fn languages(&self) -> Vec<String> {
+        self.languages.clone()
+    }
+
+    async fn lookup(&self, word: &str, options: &LookupOptions) -> Result<Vec<Thesaurus>, ApiError> {
+        // Entries with the same etymology belong to the same word; different etymologies are homographs.
+        let mut results: Vec<Thesaurus> = Vec::new();
+        for entry in self.entries(word, &options.language)? {
+            let meaning = entry.to_meaning();
+            match results.iter_mut().find(|t| t.word.as_deref() == Some(entry.word.as_str()) && t.origin == entry.etymology) {
+                Some(thesaurus) => thesaurus.meanings.get_or_insert_with(Vec::new).push(meaning),
+                None => {
+                    let mut thesaurus = entry.to_thesaurus();
+                    thesaurus.meanings = Some(vec![meaning]);
+                    results.push(thesaurus);
+                }
+            }
+        }
+        if results.is_empty() {
+            return Err(ApiError::NotFound);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const EXTRACT: &str = r#"{"word": "bass", "lang_code": "en", "pos": "noun", "etymology_text": "From Middle English bace.", "sounds": [{"ipa": "/beɪs/"}, {"mp3_url": "https://example.org/bass.mp3"}], "senses": [{"glosses": ["A low spectral range of sound."], "examples": [{"text": "Turn up the bass."}], "synonyms": [{"word": "low end"}]}]}
+{"word": "bass", "lang_code": "en", "pos": "adj", "etymology_text": "From Middle English bace.", "senses": [{"glosses": ["Of sound, low in pitch."], "antonyms": [{"word": "treble"}]}]}
+{"word": "bass", "lang_code": "en", "pos": "noun", "etymology_text": "From Old English bærs.", "senses": [{"glosses": ["A perch-like fish."]}]}
+{"word": "Bass", "lang_code": "de", "pos": "noun", "senses": [{"glosses": ["bass (voice)"]}]}
+not json
+"#;
+
+    #[tokio::test]
+    async fn test_import_and_lookup() {
+        let store = std::env::temp_dir().join(format!("thesaurust-wiktionary-{}", std::process::id()));
+        let summary = import(EXTRACT.as_bytes(), &store).unwrap();
+        assert_eq!(ImportSummary { entries: 4, words: 1, skipped: 1 }, summary);
+
+        let wiktionary = Wiktionary::open(&store).unwrap();
+        assert_eq!(vec![String::from("de"), String::from("en")], wiktionary.languages());
+        let entries = wiktionary.entries("Bass", "en").unwrap();
+        assert_eq!(3, entries.len());
+        assert_eq!(
+            vec![
+                Pronunciation { ipa: Some(String::from("/beɪs/")), audio: None },
+                Pronunciation { ipa: None, audio: Some(String::from("https://example.org/bass.mp3")) }
+            ],
+            entries[0].pronunciations
+        );
+
+        let results = wiktionary.lookup("bass", &LookupOptions::default()).await.unwrap();
+        assert_eq!(2, results.len());
+        assert_eq!(Some(String::from("From Middle English bace.")), results[0].origin);
+        assert_eq!(Some(String::from("/beɪs/")), results[0].phonetic);
+        assert_eq!(Some(vec![String::from("https://en.wiktionary.org/wiki/bass")]), results[0].sourceUrls);
+        let meanings = results[0].meanings.clone().unwrap();
+        assert_eq!(Some(String::from("adjective")), meanings[1].partOfSpeech);
+        let definition = meanings[0].definitions.clone().unwrap()[0].clone();
+        assert_eq!(Some(String::from("Turn up the bass.")), definition.example);
+        assert_eq!(Some(vec![String::from("low end")]), definition.synonyms);
+        assert_eq!(None, definition.antonyms);
+        assert!(wiktionary.lookup("treble", &LookupOptions::default()).await.is_err());
+
+        let german = LookupOptions { language: String::from("de"), ..LookupOptions::default() };
+        assert_eq!(1, wiktionary.lookup("bass", &german).await.unwrap().len());
+        fs::remove_dir_all(store).unwrap();
+    }
+}
@@ -0,0 +1,329 @@
+use std::{
+    cmp::Ordering,
+    env,
+    fs::File,
+    io::{ self, BufRead, BufReader, Seek, SeekFrom },
+    path::{ Path, PathBuf },
+};
+
+use async_trait::async_trait;
+
+use crate::models::{ data::{ Definition, Meaning, Thesaurus }, errors::ApiError };
+use crate::providers::{ text::split_gloss, DictionaryProvider, LookupOptions };
+
+/// Environment variable that WordNet's own tools read the database location from.
+const SEARCH_DIR_ENV: &str = "WNSEARCHDIR";
+const DEFAULT_DIR: &str = "/usr/share/wordnet";
+
+/// Syntactic categories in the order they are shown, with their file suffix and display name.
+const PARTS_OF_SPEECH: [(&str, &str); 4] = [
+    ("noun", "noun"),
+    ("verb", "verb"),
+    ("adj", "adjective"),
+    ("adv", "adverb"),
+];
+
+/// Looks words up from a local Princeton WordNet 3.x database.
+#[derive(Clone, Debug)]
+pub struct WordNet {
+    dir: PathBuf,
+}
+
+impl WordNet {
+    /// Opens the database in `dir`, or in `$WNSEARCHDIR` or `/usr/share/wordnet` if `dir` is `None`.
+    pub fn open(dir: Option<PathBuf>) -> io::Result<WordNet> {
+        let dir = dir
+            .or_else(|| env::var_os(SEARCH_DIR_ENV).map(PathBuf::from))
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_DIR));
+        for (suffix, _) in PARTS_OF_SPEECH {
+            for prefix in ["index", "data"] {
+                let path = dir.join(format!("{}.{}", prefix, suffix));
+                if !path.is_file() {
+                    return Err(
+                        io::Error::new(
+                            io::ErrorKind::NotFound,
+                            format!("WordNet file {} not found", path.display())
+                        )
+                    );
+                }
+            }
+        }
+        Ok(WordNet { dir })
+    }
+
+    fn meaning_for(&self, lemma: &str, suffix: &str, name: &str) -> io::Result<Option<Meaning>> {
+        let index = self.dir.join(format!("index.{}", suffix));
+        let line = match search_index(&index, lemma)? {
+            Some(line) => line,
+            None => {
+                return Ok(None);
+            }
+        };
+        let mut data = File::open(self.dir.join(format!("data.{}", suffix)))?;
+        let mut definitions = Vec::new();
+        for offset in parse_index_line(&line) {
+            let synset = read_synset(&mut data, offset)?;
+            let antonyms = self.antonyms_of(lemma, &synset)?;
+            definitions.push(synset.into_definition(lemma, antonyms));
+        }
+        Ok(
+            Some(Meaning {
+                partOfSpeech: Some(name.to_string()),
+                definitions: Some(definitions),
+                ..Meaning::default()
+            })
+        )
+    }
+
+    /// Follows the lexical antonym pointers that start from `lemma` in `synset`.
+    fn antonyms_of(&self, lemma: &str, synset: &Synset) -> io::Result<Vec<String>> {
+        let source = match synset.words.iter().position(|w| w.eq_ignore_ascii_case(lemma)) {
+            Some(idx) => idx + 1,
+            None => {
+                return Ok(Vec::new());
+            }
+        };
+        let mut antonyms = Vec::new();
+        for pointer in synset.pointers.iter().filter(|p| p.symbol == "!" && p.source == source) {
+            let mut data = File::open(self.dir.join(format!("data.{}", data_suffix(pointer.pos))))?;
+            let target = read_synset(&mut data, pointer.offset)?;
+            if let Some(word) = target.words.get(pointer.target.saturating_sub(1)) {
+                antonyms.push(word.clone());
+            }
+        }
+        Ok(antonyms)
+    }
+}
+
+#[async_trait]
+impl DictionaryProvider for WordNet {
+    fn name(&self) -> &str {
+        "wordnet"
+    }
+
+    fn is_local(&self, _options: &LookupOptions) -> bool {
+        true
+    }
+
+    fn languages(&self) -> Vec<String> {
+        vec![String::from("en")]
+    }
+
+    async fn lookup(&self, word: &str, options: &LookupOptions) -> Result<Vec<Thesaurus>, ApiError> {
+        // Princeton WordNet only covers English.
+        if options.language != "en" {
+            return Err(ApiError::NotFound);
+        }
+        let lemma = word.trim().to_lowercase().replace(' ', "_");
+        let mut meanings = Vec::new();
+        for (suffix, name) in PARTS_OF_SPEECH {
+            if let Some(meaning) = self.meaning_for(&lemma, suffix, name)? {
+                meanings.push(meaning);
+            }
+        }
+        if meanings.is_empty() {
+            return Err(ApiError::NotFound);
+        }
+        Ok(
+            vec![Thesaurus {
+                word: Some(word.trim().to_string()),
+                meanings: Some(meanings),
+                ..Thesaurus::default()
+            }]
+        )
+    }
+}
+
+/// A pointer from one synset (or one of its words) to another.
+#[derive(Clone, Debug, PartialEq)]
+struct Pointer {
+    symbol: String,
+    offset: u64,
+    pos: char,
+    /// 1-based word numbers; 0 means the pointer relates whole synsets.
+    source: usize,
+    target: usize,
+}
+
+/// A set of synonyms as stored in a `data.*` file.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Synset {
+    words: Vec<String>,
+    pointers: Vec<Pointer>,
+    gloss: String,
+}
+
+impl Synset {
+    fn into_definition(self, lemma: &str, antonyms: Vec<String>) -> Definition {
+        let (definition, examples) = split_gloss(&self.gloss);
+        let synonyms: Vec<String> = self.words
+            .iter()
+            .filter(|w| !w.eq_ignore_ascii_case(lemma))
+            .map(|w| w.replace('_', " "))
+            .collect();
+        Definition {
+            definition: Some(definition),
+            example: examples.into_iter().next(),
+            synonyms: Some(synonyms),
+            antonyms: Some(antonyms.iter().map(|w| w.replace('_', " ")).collect()),
+            source: None,
+        }
+    }
+}
+
+fn data_suffix(pos: char) -> &'static str {
+    match pos {
+        'n' => "noun",
+        'v' => "verb",
+        'a' | 's' => "adj",
+        _ => "adv",
+    }
+}
+
+/// Returns the synset offsets listed at the end of an `index.*` line.
+fn parse_index_line(line: &str) -> Vec<u64> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let synset_cnt: usize = fields.get(2).and_then(|f| f.parse().ok()).unwrap_or(0);
+    fields
+        .iter()
+        .rev()
+        .take(synset_cnt)
+        .rev()
+        .filter_map(|f| f.parse().ok())
+        .collect()
+}
+
+fn read_synset(data: &mut File, offset: u64) -> io::Result<Synset> {
+    data.seek(SeekFrom::Start(offset))?;
+    let mut line = String::new();
+    BufReader::new(data).read_line(&mut line)?;
+    parse_data_line(&line).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidData, format!("malformed synset at {}", offset))
+    })
+}
+
+/// Parses a line of a `data.*` file.
+fn parse_data_line(line: &str) -> Option<Synset> {
+    let (fields, gloss) = line.split_once(" | ").unwrap_or((line, ""));
+    let mut fields = fields.split_whitespace().skip(3);
+    let w_cnt = usize::from_str_radix(fields.next()?, 16).ok()?;
+    let mut words = Vec::with_capacity(w_cnt);
+    for _ in 0..w_cnt {
+        let word = fields.next()?;
+        // Adjectives can carry a syntactic marker such as `(a)` or `(ip)`.
+        let word = word.split_once('(').map_or(word, |(w, _)| w);
+        words.push(word.to_string());
+        fields.next()?;
+    }
+    let p_cnt: usize = fields.next()?.parse().ok()?;
+    let mut pointers = Vec::with_capacity(p_cnt);
+    for _ in 0..p_cnt {
+        let symbol = fields.next()?.to_string();
+        let offset = fields.next()?.parse().ok()?;
+        let pos = fields.next()?.chars().next()?;
+        let source_target = fields.next()?;
+        let source = usize::from_str_radix(source_target.get(..2)?, 16).ok()?;
+        let target = usize::from_str_radix(source_target.get(2..)?, 16).ok()?;
+        pointers.push(Pointer { symbol, offset, pos, source, target });
+    }
+    Some(Synset {
+        words,
+        pointers,
+        gloss: gloss.trim().to_string(),
+    })
+}
+
+/// Binary searches a sorted `index.*` file for the line that starts with `key`.
+fn search_index(path: &Path, key: &str) -> io::Result<Option<String>> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let (mut lo, mut hi) = (0, reader.get_ref().metadata()?.len());
+    let mut line = String::new();
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        // Step back one byte so that a line starting exactly at `mid` is not skipped.
+        reader.seek(SeekFrom::Start(mid.saturating_sub(1)))?;
+        let start = if mid == 0 {
+            0
+        } else {
+            line.clear();
+            mid - 1 + (reader.read_line(&mut line)? as u64)
+        };
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            hi = mid;
+            continue;
+        }
+        let lemma = line.split(' ').next().unwrap_or("");
+        match lemma.cmp(key) {
+            Ordering::Equal => {
+                return Ok(Some(line.trim_end().to_string()));
+            }
+            Ordering::Less => {
+                lo = start + (line.len() as u64);
+            }
+            Ordering::Greater => {
+                hi = mid;
+            }
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const GOOD: &str =
+        "01123148 00 a 01 good 0 005 = 04849241 n 0000 = 05142180 n 0000 + 05142180 n 0101 ! 01125429 a 0101 & 01124073 a 0000 | having desirable or positive qualities especially those suitable for a thing specified; \"good news from the hospital\"; \"a good report card\"  \n";
+
+    #[test]
+    fn test_parse_data_line() {
+        let synset = parse_data_line(GOOD).unwrap();
+        assert_eq!(vec![String::from("good")], synset.words);
+        assert_eq!(5, synset.pointers.len());
+        assert_eq!(
+            Pointer {
+                symbol: String::from("!"),
+                offset: 1125429,
+                pos: 'a',
+                source: 1,
+                target: 1,
+            },
+            synset.pointers[3]
+        );
+    }
+
+    #[test]
+    fn test_parse_data_line_strips_adjective_markers() {
+        let line = "00003356 00 s 02 unabridged(a) 0 full-length 0 000 | (used of texts) not shortened";
+        let synset = parse_data_line(line).unwrap();
+        assert_eq!(vec![String::from("unabridged"), String::from("full-length")], synset.words);
+        assert_eq!("(used of texts) not shortened", synset.gloss);
+    }
+
+    #[test]
+    fn test_parse_index_line() {
+        let line = "dog n 7 5 @ ~ #m #p %p 7 1 02084071 10114209 10023039 09886220 07676602 03907626 02710044";
+        assert_eq!(7, parse_index_line(line).len());
+        assert_eq!(2084071, parse_index_line(line)[0]);
+    }
+
+    #[test]
+    fn test_search_index() {
+        let path = env::temp_dir().join(format!("thesaurust-index-{}", std::process::id()));
+        let contents =
+            "  1 This software and database is being provided\n\
+             cat n 1 0 1 0 02121620\n\
+             dog n 1 0 1 0 02084071\n\
+             fish n 1 0 1 0 02512053\n";
+        std::fs::write(&path, contents).unwrap();
+        for key in ["cat", "dog", "fish"] {
+            let line = search_index(&path, key).unwrap().unwrap();
+            assert!(line.starts_with(key));
+        }
+        assert_eq!(None, search_index(&path, "cow").unwrap());
+        assert_eq!(None, search_index(&path, "zebra").unwrap());
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,585 @@
+use std::{
+    collections::{ HashMap, HashSet },
+    fs,
+    io,
+    path::{ Path, PathBuf },
+    sync::OnceLock,
+};
+
+use crate::spelling::{ edit_distance, Speller };
+
+/// Letters tried when a dictionary does not list its own with `TRY`.
+const DEFAULT_TRY: &str = "esianrtolcdugmphbyfvkwzxjq";
+
+/// The farthest that a suggestion found by comparing against every word can be.
+const MAX_DISTANCE: usize = 2;
+
+/// How flags are written in the `.aff` and `.dic` files.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum FlagType {
+    /// One character per flag.
+    #[default]
+    Char,
+    /// Two characters per flag.
+    Long,
+    /// Comma-separated numbers.
+    Num,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum CharClass {
+    Any,
+    Char(char),
+    Set(bool, Vec<char>),
+}
+
+impl CharClass {
+    fn matches(&self, c: char) -> bool {
+        match self {
+            CharClass::Any => true,
+            CharClass::Char(expected) => *expected == c,
+            CharClass::Set(negated, chars) => chars.contains(&c) != *negated,
+        }
+    }
+}
+
+/// Parses an affix condition such as `[^aeiou]y`.
+fn parse_condition(condition: &str) -> Vec<CharClass> {
+    if condition == "." {
+        return Vec::new();
+    }
+    let mut classes = Vec::new();
+    let mut chars = condition.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '.' => classes.push(CharClass::Any),
+            '[' => {
+                let set: Vec<char> = chars.by_ref().take_while(|c| *c != ']').collect();
+                match set.split_first() {
+                    Some(('^', rest)) => classes.push(CharClass::Set(true, rest.to_vec())),
+                    _ => classes.push(CharClass::Set(false, set)),
+                }
+            }
+            c => classes.push(CharClass::Char(c)),
+        }
+    }
+    classes
+}
+
+/// A `PFX` or `SFX` rule: remove `strip` from the stem and add `add` to it, if the stem
+/// matches `condition` at the end it is added to.
+#[derive(Clone, Debug)]
+struct Affix {
+    flag: u32,
+    cross_product: bool,
+    strip: String,
+    add: String,
+    condition: Vec<CharClass>,
+}
+
+impl Affix {
+    /// Whether the condition holds at the start (prefixes) or end (suffixes) of `stem`.
+    fn allows(&self, stem: &str, is_prefix: bool) -> bool {
+        let chars: Vec<char> = stem.chars().collect();
+        if chars.len() < self.condition.len() {
+            return false;
+        }
+        let window = if is_prefix {
+            &chars[..self.condition.len()]
+        } else {
+            &chars[chars.len() - self.condition.len()..]
+        };
+        window
+            .iter()
+            .zip(&self.condition)
+            .all(|(c, class)| class.matches(*c))
+    }
+
+    /// Undoes the prefix in `word`, returning the stem it was added to.
+    fn strip_prefix(&self, word: &str) -> Option<String> {
+        let rest = word.strip_prefix(self.add.as_str())?;
+        let stem = format!("{}{}", self.strip, rest);
+        (!stem.is_empty() && self.allows(&stem, true)).then_some(stem)
+    }
+
+    /// Undoes the suffix in `word`, returning the stem it was added to.
+    fn strip_suffix(&self, word: &str) -> Option<String> {
+        let rest = word.strip_suffix(self.add.as_str())?;
+        let stem = format!("{}{}", rest, self.strip);
+        (!stem.is_empty() && self.allows(&stem, false)).then_some(stem)
+    }
+}
+
+/// A Hunspell dictionary: the word list of a `.dic` file and the affix rules of its `.aff` file.
+/// Compounding and twofold affixes are not supported.
+#[derive(Debug, Default)]
+pub struct Hunspell {
+    flag_type: FlagType,
+    aliases: Vec<Vec<u32>>,
+    prefixes: Vec<Affix>,
+    suffixes: Vec<Affix>,
+    /// Letters to try inserting and substituting, the most common first.
+    try_chars: Vec<char>,
+    /// Common mistakes, such as `f` for `ph`.
+    replacements: Vec<(String, String)>,
+    /// Whether each `PFX` and `SFX` class can be combined with affixes of the other kind.
+    cross_products: HashMap<(String, String), bool>,
+    forbidden: Option<u32>,
+    need_affix: Option<u32>,
+    no_suggest: Option<u32>,
+    words: HashMap<String, Vec<u32>>,
+}
+
+impl Hunspell {
+    /// Reads `path`, a `.dic` file, and the `.aff` file next to it.
+    pub fn open(path: &Path) -> io::Result<Hunspell> {
+        let aff = fs::read(path.with_extension("aff"))?;
+        let dic = fs::read(path)?;
+        Ok(Hunspell::from_bytes(&aff, &dic))
+    }
+
+    pub fn from_bytes(aff: &[u8], dic: &[u8]) -> Hunspell {
+        let utf8 = String::from_utf8_lossy(aff)
+            .lines()
+            .any(|l| l.trim().eq_ignore_ascii_case("SET UTF-8"));
+        let mut hunspell = Hunspell::default();
+        hunspell.parse_aff(&decode(aff, utf8));
+        hunspell.parse_dic(&decode(dic, utf8));
+        if hunspell.try_chars.is_empty() {
+            hunspell.try_chars = DEFAULT_TRY.chars().collect();
+        }
+        hunspell
+    }
+
+    fn parse_aff(&mut self, aff: &str) {
+        // The flag type changes how the flags in the rest of the file are read.
+        for line in aff.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if let ["FLAG", flag_type] = fields.as_slice() {
+                self.flag_type = match *flag_type {
+                    "long" => FlagType::Long,
+                    "num" => FlagType::Num,
+                    _ => FlagType::Char,
+                };
+            }
+        }
+        for line in aff.lines() {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields.as_slice() {
+                ["TRY", chars] => {
+                    self.try_chars = chars.chars().collect();
+                }
+                ["REP", from, to, ..] => {
+                    self.replacements.push((from.replace('_', " "), to.replace('_', " ")));
+                }
+                ["AF", flags, ..] if flags.parse::<usize>().is_err() => {
+                    self.aliases.push(self.parse_flags(flags));
+                }
+                ["FORBIDDENWORD", flag] => {
+                    self.forbidden = self.parse_flags(flag).first().copied();
+                }
+                ["NEEDAFFIX", flag] => {
+                    self.need_affix = self.parse_flags(flag).first().copied();
+                }
+                ["NOSUGGEST", flag] => {
+                    self.no_suggest = self.parse_flags(flag).first().copied();
+                }
+                [kind @ ("PFX" | "SFX"), flag, cross_product, count] if count.parse::<usize>().is_ok() => {
+                    self.cross_products.insert((kind.to_string(), flag.to_string()), *cross_product == "Y");
+                }
+                [kind @ ("PFX" | "SFX"), flag, strip, add, condition, ..] => {
+                    let cross_product = self.cross_products
+                        .get(&(kind.to_string(), flag.to_string()))
+                        .copied()
+                        .unwrap_or(false);
+                    let affix = Affix {
+                        flag: self.parse_flags(flag).first().copied().unwrap_or_default(),
+                        cross_product,
+                        strip: zero_to_empty(strip),
+                        // Continuation flags after the slash are for twofold affixes.
+                        add: zero_to_empty(add.split('/').next().unwrap_or_default()),
+                        condition: parse_condition(condition),
+                    };
+                    if *kind == "PFX" {
+                        self.prefixes.push(affix);
+                    } else {
+                        self.suffixes.push(affix);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn parse_dic(&mut self, dic: &str) {
+        for (number, line) in dic.lines().enumerate() {
+            let line = line.trim();
+            // The first line holds the number of words.
+            if line.is_empty() || (number == 0 && line.parse::<usize>().is_ok()) {
+                continue;
+            }
+            let entry = line.split(['\t', ' ']).next().unwrap_or_default();
+            let (word, flags) = match entry.split_once('/') {
+                Some((word, flags)) => (word, self.resolve_flags(flags)),
+                None => (entry, Vec::new()),
+            };
+            let existing = self.words.entry(word.to_string()).or_default();
+            for flag in flags {
+                if !existing.contains(&flag) {
+                    existing.push(flag);
+                }
+            }
+        }
+    }
+
+    /// Reads the flags of a word, which are a number into the `AF` table if the dictionary has one.
+    fn resolve_flags(&self, flags: &str) -> Vec<u32> {
+        if !self.aliases.is_empty() {
+            if let Ok(index) = flags.parse::<usize>() {
+                return self.aliases.get(index.wrapping_sub(1)).cloned().unwrap_or_default();
+            }
+        }
+        self.parse_flags(flags)
+    }
+
+    fn parse_flags(&self, flags: &str) -> Vec<u32> {
+        match self.flag_type {
+            FlagType::Char => flags.chars().map(u32::from).collect(),
+            FlagType::Long => {
+                let chars: Vec<char> = flags.chars().collect();
+                chars
+                    .chunks(2)
+                    .map(|pair| pair.iter().fold(0, |code, c| (code << 16) | u32::from(*c)))
+                    .collect()
+            }
+            FlagType::Num => flags
+                .split(',')
+                .filter_map(|n| n.trim().parse().ok())
+                .collect(),
+        }
+    }
+
+    fn has_flag(&self, word: &str, flag: Option<u32>) -> bool {
+        match (self.words.get(word), flag) {
+            (Some(flags), Some(flag)) => flags.contains(&flag),
+            _ => false,
+        }
+    }
+
+    /// Whether `stem` is in the dictionary and may take the affix with `flag`.
+    fn takes(&self, stem: &str, flag: u32) -> bool {
+        self.words.get(stem).is_some_and(|flags| flags.contains(&flag)) &&
+            !self.has_flag(stem, self.forbidden)
+    }
+
+    /// Returns the dictionary words that `word` is an inflection of, including itself
+    /// when it is in the dictionary as it is.
+    fn analyze(&self, word: &str) -> Vec<String> {
+        let mut stems = Vec::new();
+        if self.words.contains_key(word) &&
+            !self.has_flag(word, self.forbidden) &&
+            !self.has_flag(word, self.need_affix)
+        {
+            stems.push(word.to_string());
+        }
+        for suffix in &self.suffixes {
+            let stem = match suffix.strip_suffix(word) {
+                Some(stem) => stem,
+                None => {
+                    continue;
+                }
+            };
+            if self.takes(&stem, suffix.flag) {
+                stems.push(stem.clone());
+            }
+            if suffix.cross_product {
+                for prefix in self.prefixes.iter().filter(|p| p.cross_product) {
+                    if let Some(root) = prefix.strip_prefix(&stem) {
+                        if self.takes(&root, prefix.flag) && self.takes(&root, suffix.flag) {
+                            stems.push(root);
+                        }
+                    }
+                }
+            }
+        }
+        for prefix in &self.prefixes {
+            if let Some(stem) = prefix.strip_prefix(word) {
+                if self.takes(&stem, prefix.flag) {
+                    stems.push(stem);
+                }
+            }
+        }
+        let mut seen = HashSet::new();
+        stems.retain(|s| seen.insert(s.clone()));
+        stems
+    }
+
+    /// Forms of `word` to look for: as typed, in lower case and capitalized.
+    fn casings(word: &str) -> Vec<String> {
+        let lower = word.to_lowercase();
+        let mut casings = vec![word.to_string()];
+        for casing in [lower.clone(), capitalize(&lower)] {
+            if !casings.contains(&casing) {
+                casings.push(casing);
+            }
+        }
+        casings
+    }
+
+    /// Single edits of `word`, the likeliest mistakes first.
+    fn edits(&self, word: &str) -> Vec<String> {
+        let chars: Vec<char> = word.chars().collect();
+        let mut edits = Vec::new();
+        for (from, to) in &self.replacements {
+            for (i, _) in word.match_indices(from.as_str()) {
+                edits.push(format!("{}{}{}", &word[..i], to, &word[i + from.len()..]));
+            }
+        }
+        for i in 1..chars.len() {
+            let mut swapped = chars.clone();
+            swapped.swap(i - 1, i);
+            edits.push(swapped.into_iter().collect());
+        }
+        for i in 0..chars.len() {
+            edits.push(chars[..i].iter().chain(&chars[i + 1..]).collect());
+        }
+        for c in &self.try_chars {
+            for i in 0..chars.len() {
+                if chars[i] != *c {
+                    let mut replaced = chars.clone();
+                    replaced[i] = *c;
+                    edits.push(replaced.into_iter().collect());
+                }
+            }
+            for i in 0..=chars.len() {
+                let mut inserted = chars.clone();
+                inserted.insert(i, *c);
+                edits.push(inserted.into_iter().collect());
+            }
+        }
+        edits
+    }
+}
+
+impl Speller for Hunspell {
+    fn check(&self, word: &str) -> bool {
+        Self::casings(word.trim())
+            .iter()
+            .any(|w| !self.analyze(w).is_empty())
+    }
+
+    fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        let word = word.trim();
+        if limit == 0 || self.check(word) {
+            return Vec::new();
+        }
+        let mut suggestions: Vec<String> = Vec::new();
+        let add = |suggestion: String, suggestions: &mut Vec<String>| {
+            if !suggestions.contains(&suggestion) && !self.has_flag(&suggestion, self.no_suggest) {
+                suggestions.push(suggestion);
+            }
+        };
+        for edit in self.edits(&word.to_lowercase()) {
+            if suggestions.len() >= limit {
+                break;
+            }
+            if self.check(&edit) {
+                add(edit, &mut suggestions);
+            }
+        }
+        // Farther misspellings are compared against every word in the dictionary.
+        if suggestions.len() < limit {
+            let lower = word.to_lowercase();
+            let mut close: Vec<(usize, &String)> = self.words
+                .keys()
+                .filter(|w| !self.has_flag(w, self.forbidden) && !self.has_flag(w, self.need_affix))
+                .filter_map(|w| edit_distance(&lower, &w.to_lowercase(), MAX_DISTANCE).map(|d| (d, w)))
+                .collect();
+            close.sort();
+            for (_, w) in close {
+                if suggestions.len() >= limit {
+                    break;
+                }
+                add(w.clone(), &mut suggestions);
+            }
+        }
+        if word.chars().next().is_some_and(char::is_uppercase) {
+            suggestions = suggestions.iter().map(|s| capitalize(s)).collect();
+        }
+        suggestions
+    }
+
+    fn stem(&self, word: &str) -> Vec<String> {
+        let mut stems: Vec<String> = Vec::new();
+        for casing in Self::casings(word.trim()) {
+            for stem in self.analyze(&casing) {
+                if !stems.contains(&stem) {
+                    stems.push(stem);
+                }
+            }
+        }
+        stems
+    }
+}
+
+/// A dictionary that is read the first time it is needed.
+#[derive(Debug)]
+pub struct LazyHunspell {
+    path: PathBuf,
+    hunspell: OnceLock<Option<Hunspell>>,
+}
+
+impl LazyHunspell {
+    pub fn new(path: PathBuf) -> Self {
+        LazyHunspell { path, hunspell: OnceLock::new() }
+    }
+
+    fn get(&self) -> Option<&Hunspell> {
+        self.hunspell.get_or_init(|| Hunspell::open(&self.path).ok()).as_ref()
+    }
+}
+
+impl Speller for LazyHunspell {
+    fn check(&self, word: &str) -> bool {
+        self.get().is_some_and(|h| h.check(word))
+    }
+
+    fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        self.get().map_or(Vec::new(), |h| h.suggest(word, limit))
+    }
+
+    fn stem(&self, word: &str) -> Vec<String> {
+        self.get().map_or(Vec::new(), |h| h.stem(word))
+    }
+}
+
+/// Finds the `.dic` file in `dir` for `language`: `name` if one is given, then the file
+/// named after the language (`de`), its main country (`de_DE`), `_US`, and then any other
+/// country in alphabetical order (`de_AT`).
+pub fn find_dictionary(dir: &Path, language: &str, name: Option<&str>) -> Option<PathBuf> {
+    let language = language.replace('-', "_");
+    let (base, _) = language.split_once('_').unwrap_or((&language, ""));
+    let mut names: Vec<String> = name.map(str::to_string).into_iter().collect();
+    names.push(language.clone());
+    names.push(format!("{}_{}", base, base.to_uppercase()));
+    names.push(format!("{}_US", base));
+    let mut others: Vec<String> = fs
+        ::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().to_str().and_then(|n| n.strip_suffix(".dic")).map(str::to_string))
+        .filter(|n| n.starts_with(&format!("{}_", base)))
+        .collect();
+    others.sort();
+    names.extend(others);
+    names
+        .into_iter()
+        .map(|n| dir.join(format!("{}.dic", n)))
+        .find(|path| path.exists() && path.with_extension("aff").exists())
+}
+
+fn zero_to_empty(s: &str) -> String {
+    if s == "0" { String::new() } else { s.to_string() }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Decodes the files as UTF-8 if the `.aff` file says so and as ISO 8859-1 otherwise.
+fn decode(bytes: &[u8], utf8: bool) -> String {
+    if utf8 {
+        String::from_utf8_lossy(bytes).into_owned()
+    } else {
+        bytes.iter().map(|b| char::from(*b)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const AFF: &str = "SET UTF-8
+TRY esianrtolcdugmphbyfvkwzxjqESIANRTOLCDUGMPHBYFVKWZXJQ'
+REP 1
+REP f ph
+FORBIDDENWORD !
+PFX U Y 1
+PFX U 0 un .
+SFX S Y 2
+SFX S y ies [^aeiou]y
+SFX S 0 s [^y]
+SFX G Y 2
+SFX G e ing e
+SFX G 0 ing [^e]
+";
+
+    const DIC: &str = "6
+happy/U
+city/S
+make/GSU
+walk/GS
+photo/S
+teh/!
+";
+
+    fn hunspell() -> Hunspell {
+        Hunspell::from_bytes(AFF.as_bytes(), DIC.as_bytes())
+    }
+
+    #[test]
+    fn test_check() {
+        let hunspell = hunspell();
+        assert!(hunspell.check("cities"));
+        assert!(hunspell.check("making"));
+        assert!(hunspell.check("unhappy"));
+        assert!(hunspell.check("Walking"));
+        assert!(hunspell.check("unmakes"));
+        assert!(!hunspell.check("citys"));
+        assert!(!hunspell.check("unwalk"));
+        assert!(!hunspell.check("teh"));
+    }
+
+    #[test]
+    fn test_stem() {
+        let hunspell = hunspell();
+        assert_eq!(vec![String::from("city")], hunspell.stem("cities"));
+        assert_eq!(vec![String::from("make")], hunspell.stem("unmaking"));
+    }
+
+    #[test]
+    fn test_suggest() {
+        let hunspell = hunspell();
+        assert_eq!(vec![String::from("photos")], hunspell.suggest("fotos", 1));
+        assert_eq!(vec![String::from("walking")], hunspell.suggest("walkign", 1));
+        assert_eq!(vec![String::from("City")], hunspell.suggest("Citys", 1));
+        assert_eq!(Vec::<String>::new(), hunspell.suggest("cities", 3));
+    }
+
+    #[test]
+    fn test_find_dictionary() {
+        let dir = std::env::temp_dir().join(format!("thesaurust-hunspell-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in ["en_GB", "en_US", "pt_BR"] {
+            fs::write(dir.join(format!("{}.dic", name)), DIC).unwrap();
+            fs::write(dir.join(format!("{}.aff", name)), AFF).unwrap();
+        }
+        assert_eq!(Some(dir.join("en_US.dic")), find_dictionary(&dir, "en", None));
+        assert_eq!(Some(dir.join("en_GB.dic")), find_dictionary(&dir, "en", Some("en_GB")));
+        assert_eq!(Some(dir.join("pt_BR.dic")), find_dictionary(&dir, "pt-BR", None));
+        assert_eq!(None, find_dictionary(&dir, "de", None));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_long_flags() {
+        let aff = "FLAG long\nSFX Aa Y 1\nSFX Aa 0 s .\n";
+        let hunspell = Hunspell::from_bytes(aff.as_bytes(), "1\ncat/Aa\n".as_bytes());
+        assert!(hunspell.check("cats"));
+    }
+}
@@ -0,0 +1,70 @@
+//! Spelling correction that works without an API key or a network connection.
+
+pub mod hunspell;
+pub mod symspell;
+
+/// Checks the spelling of words and suggests corrections for them.
+pub trait Speller: Send + Sync {
+    /// Whether `word` is spelled correctly.
+    fn check(&self, word: &str) -> bool;
+
+    /// Returns up to `limit` corrections for `word`, the most likely first.
+    fn suggest(&self, word: &str, limit: usize) -> Vec<String>;
+
+    /// Returns the dictionary forms that `word` is an inflection of, such as `city` for `cities`.
+    /// Spellers that know nothing about inflection return an empty list.
+    fn stem(&self, _word: &str) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// A speller and the language code of the words it knows.
+pub type LanguageSpeller = (String, Box<dyn Speller>);
+
+/// Counts the edits needed to turn `a` into `b`, where an edit inserts, deletes or
+/// substitutes one character or swaps two adjacent ones (optimal string alignment).
+/// Stops counting and returns `None` once more than `max` edits are needed.
+pub fn edit_distance(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+    // Three rows of the distance matrix: two rows back, the previous one and the current one.
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current: Vec<usize> = vec![0; b.len() + 1];
+    for i in 1..=a.len() {
+        current[0] = i;
+        let mut smallest = current[0];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (previous[j] + 1).min(current[j - 1] + 1).min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+            smallest = smallest.min(current[j]);
+        }
+        if smallest > max {
+            return None;
+        }
+        std::mem::swap(&mut before, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+    Some(previous[b.len()]).filter(|distance| *distance <= max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(Some(0), edit_distance("happy", "happy", 2));
+        assert_eq!(Some(1), edit_distance("hapy", "happy", 2));
+        assert_eq!(Some(1), edit_distance("hpapy", "happy", 2));
+        assert_eq!(Some(3), edit_distance("kitten", "sitting", 3));
+        assert_eq!(None, edit_distance("happy", "sad", 2));
+    }
+}
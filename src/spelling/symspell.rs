@@ -0,0 +1,229 @@
+use std::{
+    collections::{ hash_map::DefaultHasher, HashMap, HashSet },
+    fs::File,
+    hash::{ Hash, Hasher },
+    io::{ self, BufRead, BufReader, Read },
+    path::{ Path, PathBuf },
+    sync::OnceLock,
+};
+
+use crate::spelling::{ edit_distance, Speller };
+
+/// Only the start of each word is used to find candidates, which keeps the index small.
+const PREFIX_LENGTH: usize = 7;
+
+/// A correction and how far it is from the misspelled word.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Suggestion {
+    pub word: String,
+    pub distance: usize,
+    pub count: u64,
+}
+
+/// Corrects words against a word list with the symmetric delete algorithm (SymSpell).
+/// Every word is indexed under the strings left by deleting up to `max_distance` of its
+/// characters, so a misspelling finds its candidates by looking up its own deletes.
+#[derive(Debug)]
+pub struct SymSpell {
+    max_distance: usize,
+    /// Words and how often they occur, in the order they were read.
+    words: Vec<(String, u64)>,
+    positions: HashMap<String, usize>,
+    /// Hashes of deletes, mapped to the words that produce them. Built on the first lookup.
+    deletes: OnceLock<HashMap<u64, Vec<u32>>>,
+}
+
+impl SymSpell {
+    /// Reads a word list with one word per line. A count after the word, as in `the 23135851162`,
+    /// ranks it against others at the same distance; words without one count once.
+    pub fn from_reader(reader: impl Read, max_distance: usize) -> io::Result<SymSpell> {
+        let mut words: Vec<(String, u64)> = Vec::new();
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        for line in BufReader::new(reader).lines() {
+            let line = line?;
+            let mut fields = line.split_whitespace();
+            let word = match fields.next() {
+                Some(word) => word.to_lowercase(),
+                None => {
+                    continue;
+                }
+            };
+            let count = fields
+                .next()
+                .and_then(|c| c.parse().ok())
+                .unwrap_or(1);
+            match positions.get(&word) {
+                Some(&position) => {
+                    words[position].1 += count;
+                }
+                None => {
+                    positions.insert(word.clone(), words.len());
+                    words.push((word, count));
+                }
+            }
+        }
+        Ok(SymSpell { max_distance, words, positions, deletes: OnceLock::new() })
+    }
+
+    pub fn open(path: &Path, max_distance: usize) -> io::Result<SymSpell> {
+        Self::from_reader(File::open(path)?, max_distance)
+    }
+
+    fn deletes(&self) -> &HashMap<u64, Vec<u32>> {
+        self.deletes.get_or_init(|| {
+            let mut deletes: HashMap<u64, Vec<u32>> = HashMap::new();
+            for (position, (word, _)) in self.words.iter().enumerate() {
+                for delete in deletes_of(&prefix(word), self.max_distance) {
+                    deletes.entry(hash(&delete)).or_default().push(position as u32);
+                }
+            }
+            deletes
+        })
+    }
+
+    /// Returns the words within `max_distance` edits of `word`, the closest first and the
+    /// most common first among those at the same distance.
+    pub fn lookup(&self, word: &str, limit: usize) -> Vec<Suggestion> {
+        let word = word.trim().to_lowercase();
+        let mut suggestions: Vec<Suggestion> = Vec::new();
+        let mut seen: HashSet<u32> = HashSet::new();
+        for delete in deletes_of(&prefix(&word), self.max_distance) {
+            for &position in self.deletes().get(&hash(&delete)).into_iter().flatten() {
+                if !seen.insert(position) {
+                    continue;
+                }
+                let (candidate, count) = &self.words[position as usize];
+                if let Some(distance) = edit_distance(&word, candidate, self.max_distance) {
+                    suggestions.push(Suggestion { word: candidate.clone(), distance, count: *count });
+                }
+            }
+        }
+        suggestions.sort_by(|a, b| {
+            a.distance
+                .cmp(&b.distance)
+                .then(b.count.cmp(&a.count))
+                .then(a.word.cmp(&b.word))
+        });
+        suggestions.truncate(limit);
+        suggestions
+    }
+}
+
+impl Speller for SymSpell {
+    fn check(&self, word: &str) -> bool {
+        self.positions.contains_key(&word.trim().to_lowercase())
+    }
+
+    fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        self.lookup(word, limit + 1)
+            .into_iter()
+            .filter(|s| s.distance > 0)
+            .take(limit)
+            .map(|s| s.word)
+            .collect()
+    }
+}
+
+/// A word list that is read the first time it is needed, since the system one is large.
+#[derive(Debug)]
+pub struct LazySymSpell {
+    path: PathBuf,
+    max_distance: usize,
+    symspell: OnceLock<Option<SymSpell>>,
+}
+
+impl LazySymSpell {
+    pub fn new(path: PathBuf, max_distance: usize) -> Self {
+        LazySymSpell { path, max_distance, symspell: OnceLock::new() }
+    }
+
+    fn get(&self) -> Option<&SymSpell> {
+        self.symspell.get_or_init(|| SymSpell::open(&self.path, self.max_distance).ok()).as_ref()
+    }
+}
+
+impl Speller for LazySymSpell {
+    fn check(&self, word: &str) -> bool {
+        self.get().is_some_and(|s| s.check(word))
+    }
+
+    fn suggest(&self, word: &str, limit: usize) -> Vec<String> {
+        self.get().map_or(Vec::new(), |s| s.suggest(word, limit))
+    }
+}
+
+fn prefix(word: &str) -> String {
+    word.chars().take(PREFIX_LENGTH).collect()
+}
+
+fn hash(s: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    s.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Returns `word` and every string left by deleting up to `max` of its characters.
+fn deletes_of(word: &str, max: usize) -> HashSet<String> {
+    let mut deletes = HashSet::from([word.to_string()]);
+    let mut edges = vec![word.to_string()];
+    for _ in 0..max {
+        let mut next = Vec::new();
+        for edge in &edges {
+            let chars: Vec<char> = edge.chars().collect();
+            for i in 0..chars.len() {
+                let delete: String = chars[..i].iter().chain(&chars[i + 1..]).collect();
+                if deletes.insert(delete.clone()) {
+                    next.push(delete);
+                }
+            }
+        }
+        edges = next;
+    }
+    deletes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const WORDS: &str = "happy 500\nhappen 300\nharpy 20\nhippy 10\nhappily\nsappy 40\nthesaurus\n";
+
+    #[test]
+    fn test_lookup_ranks_candidates() {
+        let symspell = SymSpell::from_reader(WORDS.as_bytes(), 2).unwrap();
+        let suggestions = symspell.suggest("hapy", 3);
+        assert_eq!(vec!["happy", "harpy", "sappy"], suggestions);
+    }
+
+    #[test]
+    fn test_lookup_with_transposition() {
+        let symspell = SymSpell::from_reader(WORDS.as_bytes(), 2).unwrap();
+        let suggestions = symspell.lookup("thesuarus", 1);
+        assert_eq!(
+            vec![Suggestion { word: String::from("thesaurus"), distance: 1, count: 1 }],
+            suggestions
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let symspell = SymSpell::from_reader(WORDS.as_bytes(), 2).unwrap();
+        assert!(symspell.check("Happy"));
+        assert!(!symspell.check("hapy"));
+        assert_eq!(Vec::<String>::new(), symspell.suggest("xylophone", 5));
+    }
+
+    #[test]
+    fn test_lazy_word_list() {
+        let path = std::env::temp_dir().join(format!("thesaurust-{}-words", std::process::id()));
+        let symspell = LazySymSpell::new(path.clone(), 2);
+        std::fs::write(&path, WORDS).unwrap();
+        assert!(symspell.check("happy"));
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(vec!["happy"], symspell.suggest("hapy", 1));
+
+        let missing = LazySymSpell::new(path, 2);
+        assert!(!missing.check("happy"));
+    }
+}
@@ -4,8 +4,11 @@ use ratatui::{ layout::{ Direction, Layout, Rect }, prelude::Constraint };
 
 use crate::{
     components::{
+        antonym_block,
+        attribution,
         banner_block,
         definition_block,
+        error_block,
         example_block,
         footer,
         part_of_speech_block,
@@ -44,7 +47,9 @@ pub fn render(app: &mut App, f: &mut Frame) {
         }
         _ => {
             f.render_widget(search_bar::new(app), upper_frame[0]);
-            if !app.results.is_empty() {
+            if let Some(error) = &app.error {
+                f.render_widget(error_block::new(error), create_upper_layout(main_frame[1])[0]);
+            } else if !app.results.is_empty() {
                 render_part_of_speech_block(app, f, lower_frame[0]);
                 render_right_frame_components(app, f, right_frame);
                 render_synonym_block(app, f, lower_frame[2]);
@@ -113,14 +118,30 @@ fn create_lower_layout(area: Rect) -> Rc<[Rect]> {
 
 fn render_synonym_block(app: &mut App, f: &mut Frame, area: Rect) {
     let mut cloned_state = app.synonym_list.state.clone();
-    f.render_stateful_widget(synonym_block::new(app), area, &mut cloned_state);
+    if app.antonyms.is_empty() {
+        f.render_stateful_widget(synonym_block::new(app), area, &mut cloned_state);
+        return;
+    }
+    let areas = create_right_layout(area);
+    f.render_stateful_widget(synonym_block::new(app), areas[0], &mut cloned_state);
+    f.render_widget(antonym_block::new(app), areas[1]);
 }
 
 fn render_right_frame_components(app: &mut App, f: &mut Frame, right_frame: Rc<[Rect]>) {
     let pos_list_idx = app.part_of_speech_list.state.selected().unwrap_or(0);
     let definition_list_idx = app.definition_list.state.selected().unwrap_or(0);
-    let definitions = Thesaurus::unwrap_meanings_at(pos_list_idx, &app.results[0]).1;
-    let d = definitions[definition_list_idx].clone();
+    let definitions = match app.entry() {
+        Some(entry) => Thesaurus::unwrap_meanings_at(pos_list_idx, entry).1,
+        None => {
+            return;
+        }
+    };
+    let d = match definitions.get(definition_list_idx) {
+        Some(d) => d.clone(),
+        None => {
+            return;
+        }
+    };
     let definition = d.definition.unwrap_or("".to_string());
     let example = d.example.unwrap_or("".to_string());
     f.render_widget(definition_block::new(app, definitions, definition), right_frame[0]);
@@ -128,8 +149,7 @@ fn render_right_frame_components(app: &mut App, f: &mut Frame, right_frame: Rc<[
 }
 
 fn render_part_of_speech_block(app: &mut App, f: &mut Frame, area: Rect) {
-    let meanings = app.results[0].meanings.clone();
-    if meanings.is_some() {
+    if app.entry().is_some_and(|entry| entry.meanings.is_some()) {
         let mut cloned_state = app.part_of_speech_list.state.clone();
         f.render_stateful_widget(part_of_speech_block::new(app), area, &mut cloned_state);
     }
@@ -137,6 +157,13 @@ fn render_part_of_speech_block(app: &mut App, f: &mut Frame, area: Rect) {
 
 fn render_instructions(app: &mut App, f: &mut Frame, frame: Rc<[Rect]>) {
     let instructions = App::update_instructions(app);
+    if let Some(retry) = app.retry.as_ref().filter(|_| app.lookup.is_some()) {
+        f.render_widget(footer::status(retry), frame[0]);
+    } else if !matches!(app.input_mode, InputMode::Suggesting) {
+        if let Some(thesaurus) = app.entry() {
+            f.render_widget(attribution::new(thesaurus, app.notice.as_deref()), frame[0]);
+        }
+    }
     f.render_widget(footer::with(&instructions), frame[1]);
     f.render_widget(footer::with("default"), frame[2]);
 }